@@ -40,6 +40,24 @@ pub const KEY_SPEC_USER_SESSION_KEYRING:    KeyringSerial = unsafe { KeyringSeri
 pub const KEY_SPEC_GROUP_KEYRING:           KeyringSerial = unsafe { KeyringSerial::new_unchecked(-6) };
 pub const KEY_SPEC_REQKEY_AUTH_KEY:         KeyringSerial = unsafe { KeyringSerial::new_unchecked(-7) };
 
+pub const KEYCTL_MOVE:                      u32 = 30;
+pub const KEYCTL_CAPABILITIES:              u32 = 31;
+pub const KEYCTL_WATCH_KEY:                 u32 = 32;
+
+pub const KEYCTL_MOVE_EXCL:                 u32 = 0x01;
+
+pub const KEYCTL_CAPS0_CAPABILITIES:        u8 = 0x01;
+pub const KEYCTL_CAPS0_PERSISTENT_KEYRINGS: u8 = 0x02;
+pub const KEYCTL_CAPS0_DIFFIE_HELLMAN:      u8 = 0x04;
+pub const KEYCTL_CAPS0_PUBLIC_KEY:          u8 = 0x08;
+pub const KEYCTL_CAPS0_BIG_KEY:             u8 = 0x10;
+pub const KEYCTL_CAPS0_INVALIDATE:          u8 = 0x20;
+pub const KEYCTL_CAPS0_RESTRICT_KEYRING:    u8 = 0x40;
+pub const KEYCTL_CAPS0_MOVE:                u8 = 0x80;
+pub const KEYCTL_CAPS1_NS_KEYRING_NAME:     u8 = 0x01;
+pub const KEYCTL_CAPS1_NS_KEY_TAG:          u8 = 0x02;
+pub const KEYCTL_CAPS1_NOTIFICATIONS:       u8 = 0x04;
+
 pub const KEYCTL_SUPPORTS_ENCRYPT:          u32 = 0x01;
 pub const KEYCTL_SUPPORTS_DECRYPT:          u32 = 0x02;
 pub const KEYCTL_SUPPORTS_SIGN:             u32 = 0x04;
@@ -31,7 +31,10 @@ use std::ptr;
 use log::error;
 use uninit::out_ref::Out;
 
-use crate::{DefaultKeyring, KeyPermissions, KeyringSerial, TimeoutSeconds};
+use crate::{
+    DefaultKeyring, KeyPermissions, KeyringSerial, TimeoutSeconds, KEYCTL_CAPABILITIES,
+    KEYCTL_MOVE, KEYCTL_WATCH_KEY,
+};
 
 /// Reexport of `Errno` as `Error`.
 type Error = errno::Errno;
@@ -61,18 +64,28 @@ static BUFFER_OVERFLOW: &str = concat!(
     env!("CARGO_PKG_REPOSITORY"),
 );
 
-fn cstring(s: &str) -> CString {
-    CString::new(s.as_bytes()).unwrap()
+fn cstring(s: &str) -> Result<CString> {
+    CString::new(s.as_bytes()).map_err(|_| errno::Errno(libc::EINVAL))
 }
 
-fn opt_cstring(opt: Option<&str>) -> Option<CString> {
-    opt.map(cstring)
+fn opt_cstring(opt: Option<&str>) -> Result<Option<CString>> {
+    opt.map(cstring).transpose()
 }
 
 fn opt_cstring_ptr(opt: &Option<CString>) -> *const libc::c_char {
     opt.as_ref().map_or(ptr::null(), |cs| cs.as_ptr())
 }
 
+// An empty slice's pointer is dangling; pass a null pointer instead so the kernel does not see
+// an address it may not accept.
+fn payload_ptr(payload: &[u8]) -> *const libc::c_void {
+    if payload.is_empty() {
+        ptr::null()
+    } else {
+        payload.as_ptr() as *const libc::c_void
+    }
+}
+
 fn opt_key_serial(opt: Option<KeyringSerial>) -> i32 {
     opt.map(KeyringSerial::get).unwrap_or(0)
 }
@@ -128,14 +141,14 @@ pub fn add_key(
     payload: &[u8],
     keyring: KeyringSerial,
 ) -> Result<KeyringSerial> {
-    let type_cstr = cstring(type_);
-    let desc_cstr = cstring(description);
+    let type_cstr = cstring(type_)?;
+    let desc_cstr = cstring(description)?;
     unsafe {
         syscall!(
             libc::SYS_add_key,
             type_cstr.as_ptr(),
             desc_cstr.as_ptr(),
-            payload.as_ptr() as *const libc::c_void,
+            payload_ptr(payload),
             payload.len(),
             keyring.get(),
         )
@@ -149,9 +162,9 @@ pub fn request_key(
     callout_info: Option<&str>,
     keyring: Option<KeyringSerial>,
 ) -> Result<KeyringSerial> {
-    let type_cstr = cstring(type_);
-    let desc_cstr = cstring(description);
-    let callout_cstr = opt_cstring(callout_info);
+    let type_cstr = cstring(type_)?;
+    let desc_cstr = cstring(description)?;
+    let callout_cstr = opt_cstring(callout_info)?;
     let callout_ptr = opt_cstring_ptr(&callout_cstr);
 
     unsafe {
@@ -178,7 +191,7 @@ pub fn keyctl_get_keyring_id(id: KeyringSerial, create: bool) -> Result<KeyringS
 }
 
 pub fn keyctl_join_session_keyring(name: Option<&str>) -> Result<KeyringSerial> {
-    let name_cstr = opt_cstring(name);
+    let name_cstr = opt_cstring(name)?;
     let name_ptr = opt_cstring_ptr(&name_cstr);
 
     unsafe { keyctl!(libc::KEYCTL_JOIN_SESSION_KEYRING, name_ptr,) }.map(keyring_serial)
@@ -189,7 +202,7 @@ pub fn keyctl_update(id: KeyringSerial, payload: &[u8]) -> Result<()> {
         keyctl!(
             libc::KEYCTL_UPDATE,
             id.get(),
-            payload.as_ptr() as *const libc::c_void,
+            payload_ptr(payload),
             payload.len(),
         )
     }
@@ -251,8 +264,8 @@ pub fn keyctl_search(
     description: &str,
     destringid: Option<KeyringSerial>,
 ) -> Result<KeyringSerial> {
-    let type_cstr = cstring(type_);
-    let desc_cstr = cstring(description);
+    let type_cstr = cstring(type_)?;
+    let desc_cstr = cstring(description)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_SEARCH,
@@ -287,7 +300,7 @@ pub fn keyctl_instantiate(
         keyctl!(
             libc::KEYCTL_INSTANTIATE,
             id.get(),
-            payload.as_ptr() as *const libc::c_void,
+            payload_ptr(payload),
             payload.len(),
             opt_key_serial(ringid),
         )
@@ -295,6 +308,36 @@ pub fn keyctl_instantiate(
     .map(ignore)
 }
 
+pub fn keyctl_instantiate_iov(
+    id: KeyringSerial,
+    payloads: &[&[u8]],
+    ringid: Option<KeyringSerial>,
+) -> Result<()> {
+    let iovs = payloads
+        .iter()
+        .map(|payload| {
+            libc::iovec {
+                iov_base: payload.as_ptr() as *mut libc::c_void,
+                iov_len: payload.len(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let count: libc::c_uint = iovs
+        .len()
+        .try_into()
+        .map_err(|_| errno::Errno(libc::EINVAL))?;
+    unsafe {
+        keyctl!(
+            libc::KEYCTL_INSTANTIATE_IOV,
+            id.get(),
+            iovs.as_ptr(),
+            count,
+            opt_key_serial(ringid),
+        )
+    }
+    .map(ignore)
+}
+
 pub fn keyctl_negate(
     id: KeyringSerial,
     timeout: TimeoutSeconds,
@@ -366,6 +409,44 @@ pub fn keyctl_session_to_parent() -> Result<()> {
     unsafe { keyctl!(libc::KEYCTL_SESSION_TO_PARENT,) }.map(ignore)
 }
 
+pub fn keyctl_watch_key(
+    id: KeyringSerial,
+    watch_queue_fd: libc::c_int,
+    watch_id: libc::c_int,
+) -> Result<()> {
+    unsafe { keyctl!(KEYCTL_WATCH_KEY, id.get(), watch_queue_fd, watch_id,) }.map(ignore)
+}
+
+pub fn keyctl_move(
+    id: KeyringSerial,
+    from_ringid: KeyringSerial,
+    to_ringid: KeyringSerial,
+    flags: u32,
+) -> Result<()> {
+    unsafe {
+        keyctl!(
+            KEYCTL_MOVE,
+            id.get(),
+            from_ringid.get(),
+            to_ringid.get(),
+            flags,
+        )
+    }
+    .map(ignore)
+}
+
+pub fn keyctl_capabilities(mut buffer: Option<Out<[u8]>>) -> Result<usize> {
+    let capacity = buffer.as_mut().map_or(0, |b| b.len());
+    unsafe {
+        keyctl!(
+            KEYCTL_CAPABILITIES,
+            buffer.as_mut().map_or(ptr::null(), |b| b.as_mut_ptr()),
+            capacity,
+        )
+    }
+    .map(size)
+}
+
 #[repr(C)]
 struct DhComputeParamsKernel {
     priv_: i32,
@@ -418,7 +499,7 @@ pub fn keyctl_dh_compute_kdf(
         prime: prime.get(),
         base: base.get(),
     };
-    let hash_cstr = cstring(hashname);
+    let hash_cstr = cstring(hashname)?;
     let kdf_params = DhKdfParamsKernel {
         hashname: hash_cstr.as_ptr(),
         otherinfo: otherinfo.map_or(ptr::null(), |d| d.as_ptr()) as *const libc::c_void,
@@ -456,8 +537,8 @@ pub fn keyctl_restrict_keyring(keyring: KeyringSerial, restriction: Restriction)
             type_,
             restriction,
         } => {
-            type_cstr = cstring(type_);
-            restriction_cstr = cstring(restriction);
+            type_cstr = cstring(type_)?;
+            restriction_cstr = cstring(restriction)?;
 
             (type_cstr.as_ptr(), restriction_cstr.as_ptr())
         },
@@ -524,7 +605,7 @@ impl From<PKeyQueryKernel> for PKeyQuery {
 
 pub fn keyctl_pkey_query(key: KeyringSerial, info: &str) -> Result<PKeyQuery> {
     let mut query = PKeyQueryKernel::zeroed();
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_QUERY,
@@ -559,7 +640,7 @@ pub fn keyctl_pkey_encrypt(
         out_len: safe_len(buffer.len())?,
         in2_len: 0,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_ENCRYPT,
@@ -584,7 +665,7 @@ pub fn keyctl_pkey_decrypt(
         out_len: safe_len(buffer.len())?,
         in2_len: 0,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_DECRYPT,
@@ -609,7 +690,7 @@ pub fn keyctl_pkey_sign(
         out_len: safe_len(buffer.len())?,
         in2_len: 0,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_SIGN,
@@ -629,7 +710,7 @@ pub fn keyctl_pkey_verify(key: KeyringSerial, info: &str, data: &[u8], sig: &[u8
         out_len: 0,
         in2_len: safe_len(sig.len())?,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_VERIFY,
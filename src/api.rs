@@ -25,58 +25,263 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::borrow::{Borrow, Cow};
-use std::convert::TryInto;
+use std::collections::{HashMap, HashSet};
+use std::convert::{TryFrom, TryInto};
+use std::ffi::CString;
+use std::fmt;
 use std::mem;
+use std::ops;
+use std::ptr;
 use std::result;
 use std::str;
 use std::time::Duration;
+use std::vec;
 
 use keyutils_raw::*;
 use log::error;
 use uninit::extension_traits::VecCapacity;
+use uninit::out_ref::Out;
 
-use crate::constants::{KeyctlSupportFlags, Permission, SpecialKeyring};
+use crate::constants::{Category, KeyctlSupportFlags, Permission, SpecialKeyring};
 use crate::keytype::*;
 use crate::keytypes;
+use crate::proc::{self, ProcKey, ProcKeyTimeout};
+use crate::watch::KeyWatch;
 
 /// Reexport of `Errno` as `Error`.
+///
+/// Errors convert into `std::io::Error` (via `io::Error::from_raw_os_error`), so `?` may be used
+/// in functions returning `io::Result`. The error of the last failed system call on the current
+/// thread is available using `errno::errno()`. See `KeyError` for descriptions of errors in terms
+/// of keys.
+///
+/// ```
+/// # use std::io;
+/// # use keyutils::Keyring;
+/// fn session_children() -> io::Result<usize> {
+///     let keyring = Keyring::attach_or_create(keyutils::SpecialKeyring::Session)?;
+///     Ok(keyring.len()?)
+/// }
+///
+/// let err: io::Error = errno::Errno(libc::ENOKEY).into();
+/// assert_eq!(err.raw_os_error(), Some(libc::ENOKEY));
+/// # let _ = session_children();
+/// ```
 pub type Error = errno::Errno;
 /// Simpler `Result` type with the error already set.
 pub type Result<T> = result::Result<T, Error>;
 
 /// Request a key from the kernel.
-fn request_impl<K: KeyType>(
+fn request_impl(
+    type_: &str,
     description: &str,
     info: Option<&str>,
     id: Option<KeyringSerial>,
 ) -> Result<KeyringSerial> {
-    request_key(K::name(), description, info, id)
+    request_key(type_, description, info, id)
 }
 
-fn read_impl(id: KeyringSerial) -> Result<Vec<u8>> {
-    // Get the size of the description.
-    let mut sz = keyctl_read(id, None)?;
-    // Allocate this description.
-    let mut buffer = vec![0; sz];
-    loop {
-        let write_buffer = buffer.get_backing_buffer();
-        // Fetch the description.
-        sz = keyctl_read(id, Some(write_buffer))?;
+// The number of times to retry reading data which keeps growing.
+const MAX_READ_ATTEMPTS: usize = 16;
+
+// How long a key which could not be fulfilled is negated for.
+const FULFILL_NEGATE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Read variable-sized data from the kernel into `buffer`.
+///
+/// The kernel reports the full size of the data even if the given buffer is too small, so the
+/// data may grow between sizing the buffer and reading into it. Reading is retried while this
+/// happens and `EAGAIN` is returned if the data keeps growing.
+fn read_sized<F>(buffer: &mut Vec<u8>, mut read: F) -> Result<usize>
+where
+    F: FnMut(Option<Out<[u8]>>) -> Result<usize>,
+{
+    buffer.clear();
+    // Get the size of the data.
+    let sz = read(None)?;
+    buffer.resize(sz, 0);
+    for _ in 0..MAX_READ_ATTEMPTS {
+        // Fetch the data.
+        let sz = read(Some((&mut buffer[..]).into()))?;
 
         // If we got everything, exit.
-        if sz <= buffer.capacity() {
-            break;
+        if sz <= buffer.len() {
+            buffer.truncate(sz);
+            return Ok(sz);
         }
 
         // Resize for the additional capacity we need.
         buffer.resize(sz, 0);
     }
-    buffer.truncate(sz);
+    Err(errno::Errno(libc::EAGAIN))
+}
+
+/// Read a NUL-terminated string from the kernel.
+///
+/// Returns `EILSEQ` if the string is not valid UTF-8 (e.g., a key description may contain any
+/// byte other than NUL).
+fn read_nul_terminated<F>(read: F) -> Result<String>
+where
+    F: FnMut(Option<Out<[u8]>>) -> Result<usize>,
+{
+    let mut buffer = Vec::new();
+    let sz = read_sized(&mut buffer, read)?;
+    // Remove 1 from the size for the trailing NUL the kernel adds.
+    buffer.truncate(sz.saturating_sub(1));
+    String::from_utf8(buffer).map_err(|_| errno::Errno(libc::EILSEQ))
+}
+
+/// Check that a serial number refers to a real (rather than special) key.
+fn checked_serial(serial: i32) -> Result<KeyringSerial> {
+    if serial > 0 {
+        KeyringSerial::new(serial).ok_or(errno::Errno(libc::EINVAL))
+    } else {
+        Err(errno::Errno(libc::EINVAL))
+    }
+}
+
+fn read_impl(id: KeyringSerial) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    read_into_impl(id, &mut buffer)?;
     Ok(buffer)
 }
 
+fn read_into_impl(id: KeyringSerial, buffer: &mut Vec<u8>) -> Result<usize> {
+    read_sized(buffer, |write_buffer| keyctl_read(id, write_buffer))
+}
+
+/// Convert a timeout into the kernel's representation.
+///
+/// The kernel uses 0 to mean "no expiration", so any timeout is at least one second.
+fn timeout_seconds(timeout: Option<Duration>) -> TimeoutSeconds {
+    timeout.map_or(0, |timeout| {
+        let secs = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+        secs.try_into().unwrap_or(TimeoutSeconds::MAX).max(1)
+    })
+}
+
+/// Overwrite a buffer which held secret material.
+fn zeroize(buffer: &mut [u8]) {
+    for byte in buffer.iter_mut() {
+        // Volatile writes so that the compiler does not elide the stores to a dead buffer.
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+}
+
+/// Pass a buffer which holds secret material to `f` and overwrite it afterwards.
+pub(crate) fn with_zeroized<F, T>(buffer: &mut [u8], f: F) -> T
+where
+    F: FnOnce(&[u8]) -> T,
+{
+    let res = f(buffer);
+    zeroize(buffer);
+    res
+}
+
+// Parse the payload of a keyring into the serials of its children.
+pub(crate) fn parse_serials(buffer: &[u8]) -> Result<Vec<KeyringSerial>> {
+    let chunk_size = mem::size_of::<KeyringSerial>();
+    buffer
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let bytes = chunk.try_into().map_err(|err| {
+                error!(
+                    "A keyring did not have the right number of bytes for a child key or keyring \
+                     ID: {}",
+                    err,
+                );
+                errno::Errno(libc::EINVAL)
+            })?;
+            let id = i32::from_ne_bytes(bytes);
+            KeyringSerial::new(id).ok_or_else(|| {
+                error!("A keyring had a child key or keyring ID of 0");
+                errno::Errno(libc::EINVAL)
+            })
+        })
+        .collect()
+}
+
+/// Replace the session keyring of the parent process with the current session keyring.
+///
+/// The parent must have the same user and group IDs as the current process, must not be
+/// running a setuid or setgid program (i.e., its credentials may not have changed), and must be
+/// single-threaded. The current session keyring must be owned by the user. If the parent is
+/// `init` or a kernel thread, `EPERM` is returned.
+///
+/// Note that the change does not happen immediately; the parent's session keyring is only
+/// replaced when the parent next returns from the kernel to userspace (e.g., after the wait for
+/// this process to exit completes).
+pub fn set_session_to_parent() -> Result<()> {
+    keyctl_session_to_parent()
+}
+
+/// Restores the default keyring when dropped.
+struct DefaultKeyringGuard {
+    old: DefaultKeyring,
+}
+
+impl Drop for DefaultKeyringGuard {
+    fn drop(&mut self) {
+        let old = mem::replace(&mut self.old, DefaultKeyring::NoChange);
+        if let Err(err) = keyctl_set_reqkey_keyring(old) {
+            error!("Failed to restore the default keyring: {}", err);
+        }
+    }
+}
+
+/// An anonymous session keyring which re-joins a named session keyring when dropped.
+///
+/// The kernel offers no way to return to a previous session keyring; a process may only join a
+/// new anonymous keyring or a named keyring. Instead, this guard joins the session keyring named
+/// by `restore` when it is dropped. If the session keyring in use before the guard was created
+/// was anonymous, the process will not return to it.
+///
+/// Created by `Keyring::join_anonymous_session_scoped`.
+#[derive(Debug)]
+pub struct SessionGuard {
+    keyring: Keyring,
+    restore: String,
+}
+
+impl SessionGuard {
+    /// The anonymous session keyring.
+    pub fn keyring(&self) -> &Keyring {
+        &self.keyring
+    }
+
+    /// The anonymous session keyring.
+    pub fn keyring_mut(&mut self) -> &mut Keyring {
+        &mut self.keyring
+    }
+
+    /// The name of the session keyring which will be joined when the guard is dropped.
+    pub fn restore(&self) -> &str {
+        &self.restore
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Err(err) = keyctl_join_session_keyring(Some(&self.restore)) {
+            error!(
+                "Failed to join the session keyring {:?}: {}",
+                self.restore, err,
+            );
+        }
+    }
+}
+
 /// Representation of a kernel keyring.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// # Thread safety
+///
+/// A `Keyring` is only a serial number, so it is `Send` and `Sync`. However, the kernel resolves
+/// some operations relative to the calling thread. A handle from `attach` refers to the keyring
+/// which the special keyring resolved to on the thread which attached it; on another thread it
+/// still refers to that keyring, but that thread may not possess it and lose access through the
+/// possessor permissions. A handle from `special` is resolved on every use, so it refers to the
+/// calling thread's special keyring wherever it is used.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Keyring {
     id: KeyringSerial,
 }
@@ -95,13 +300,30 @@ impl Keyring {
         }
     }
 
-    fn new_impl(id: KeyringSerial) -> Self {
+    /// Create a handle for an existing keyring from its serial number.
+    ///
+    /// The serial must be positive (special keyrings are available via `attach` instead) and
+    /// refer to a keyring which may be viewed, otherwise the error from the kernel is returned.
+    /// Returns `ENOTDIR` if the serial refers to a key which is not a keyring. The handle does not
+    /// take ownership of the keyring in any way; it is only a reference to it by serial number.
+    pub fn from_serial(serial: i32) -> Result<Self> {
+        let keyring = Self::new_impl(checked_serial(serial)?);
+        if keyring.description()?.type_ != keytypes::Keyring::name() {
+            return Err(errno::Errno(libc::ENOTDIR));
+        }
+        Ok(keyring)
+    }
+
+    pub(crate) fn new_impl(id: KeyringSerial) -> Self {
         Keyring {
             id,
         }
     }
 
-    pub(crate) fn serial(&self) -> KeyringSerial {
+    /// The serial number of the keyring.
+    ///
+    /// This is the ID used by the kernel for the keyring, e.g., in `/proc/keys` and by `keyctl`.
+    pub fn serial(&self) -> KeyringSerial {
         self.id
     }
 
@@ -117,6 +339,23 @@ impl Keyring {
         keyctl_set_reqkey_keyring(keyring)
     }
 
+    /// Run `f` with the default keyring set to `keyring`.
+    ///
+    /// The previous default keyring is restored afterwards, even if `f` panics.
+    ///
+    /// # Panics
+    ///
+    /// See `set_default`.
+    pub fn with_default<F, T>(keyring: DefaultKeyring, f: F) -> Result<T>
+    where
+        F: FnOnce() -> T,
+    {
+        let _guard = DefaultKeyringGuard {
+            old: Self::set_default(keyring)?,
+        };
+        Ok(f())
+    }
+
     /// Requests a keyring with the given description by searching the thread, process, and session
     /// keyrings.
     ///
@@ -132,7 +371,8 @@ impl Keyring {
         I: Into<Option<&'s str>>,
         T: Into<Option<TargetKeyring<'a>>>,
     {
-        request_impl::<keytypes::Keyring>(
+        request_impl(
+            keytypes::Keyring::name(),
             description.as_ref(),
             info.into().as_ref().copied(),
             target.into().map(TargetKeyring::serial),
@@ -144,6 +384,16 @@ impl Keyring {
         keyctl_get_keyring_id(id.serial(), create).map(Self::new_impl)
     }
 
+    /// A handle which refers to a special keyring by its special serial number.
+    ///
+    /// Unlike `attach`, the special keyring is not resolved to the keyring it currently refers to
+    /// (nor created if it does not exist). Instead, the kernel resolves it each time the handle is
+    /// used. This means that the handle follows changes to the special keyring (e.g., joining a
+    /// new session) and that it does not compare equal to a handle from `attach`.
+    pub fn special(id: SpecialKeyring) -> Self {
+        Self::new_impl(id.serial())
+    }
+
     /// Attach to a special keyring. Fails if the keyring does not already exist.
     pub fn attach(id: SpecialKeyring) -> Result<Self> {
         Self::get_keyring(id, false)
@@ -170,6 +420,72 @@ impl Keyring {
         keyctl_join_session_keyring(Some(name.as_ref())).map(Self::new_impl)
     }
 
+    /// Create a new anonymous keyring and set it as the session keyring until the returned guard
+    /// is dropped.
+    ///
+    /// When the guard is dropped, the session keyring named `restore` is joined (and created if
+    /// it does not exist). See `SessionGuard` for the limitations of restoring sessions. The
+    /// anonymous keyring is not invalidated; it is released once nothing else refers to it.
+    pub fn join_anonymous_session_scoped<N>(restore: N) -> Result<SessionGuard>
+    where
+        N: Into<String>,
+    {
+        let restore = restore.into();
+        let keyring = Self::join_anonymous_session()?;
+        Ok(SessionGuard {
+            keyring,
+            restore,
+        })
+    }
+
+    /// Run `f` with a new anonymous keyring set as the session keyring.
+    ///
+    /// Afterwards, the session keyring named `restore` is joined, even if `f` panics. See
+    /// `join_anonymous_session_scoped`.
+    pub fn with_anonymous_session<N, F, T>(restore: N, f: F) -> Result<T>
+    where
+        N: Into<String>,
+        F: FnOnce(&mut Keyring) -> T,
+    {
+        let mut guard = Self::join_anonymous_session_scoped(restore)?;
+        Ok(f(guard.keyring_mut()))
+    }
+
+    /// Create a new named keyring linked into this keyring without joining it as the session.
+    ///
+    /// Unlike `join_session`, the session keyring of the current thread is left alone; this is
+    /// the same as `add_keyring`. Requires `write` permission on the keyring.
+    ///
+    /// ```
+    /// # use keyutils::keytypes::User;
+    /// # use keyutils::{Keyring, SpecialKeyring};
+    /// # fn main() -> keyutils::Result<()> {
+    /// // Build an isolated keyring for a sandboxed task which only holds its own credentials.
+    /// let mut process = Keyring::attach_or_create(SpecialKeyring::Process)?;
+    /// let mut sandbox = process.create_detached("sandbox")?;
+    /// sandbox.add_key::<User, _, _>("token", &b"secret"[..])?;
+    /// assert_eq!(sandbox.len()?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_detached<N>(&mut self, name: N) -> Result<Self>
+    where
+        N: AsRef<str>,
+    {
+        self.add_keyring(name.as_ref())
+    }
+
+    /// Update the payload of the keyring.
+    ///
+    /// The standard keyring type does not support updates and returns `EOPNOTSUPP`; this is
+    /// intended for keyring-like types which do. Requires `write` permission on the keyring.
+    pub fn update<D>(&mut self, data: D) -> Result<()>
+    where
+        D: AsRef<[u8]>,
+    {
+        keyctl_update(self.id, data.as_ref())
+    }
+
     /// Clears the contents of the keyring.
     ///
     /// Requires `write` permission on the keyring.
@@ -177,14 +493,135 @@ impl Keyring {
         keyctl_clear(self.id)
     }
 
+    /// Clears the keyring after invalidating the keyrings nested within it.
+    ///
+    /// This is destructive: nested keyrings (at any depth) which are only linked from within
+    /// this keyring's tree are invalidated, destroying their contents as well. Keyrings which are
+    /// also referenced from elsewhere (as determined by their usage counts in `/proc/keys`) are
+    /// only unlinked and their contents are left alone, including any keyrings nested within
+    /// them. This is best-effort; nested keyrings which cannot be read, viewed, or invalidated are
+    /// skipped. Requires `write` permission on the keyring and access to `/proc/keys`.
+    pub fn clear_recursive(&mut self) -> Result<()> {
+        // Gather the keyrings which link to each nested keyring.
+        let mut parents: HashMap<KeyringSerial, Vec<KeyringSerial>> = HashMap::new();
+        let mut ancestors = vec![self.id];
+        let mut iter = self.iter()?;
+        while let Some((key, description, depth)) = iter.next_entry() {
+            ancestors.truncate(depth + 1);
+            if description.type_ == keytypes::Keyring::name() {
+                parents.entry(key.id).or_default().push(ancestors[depth]);
+                ancestors.push(key.id);
+            }
+        }
+        parents.remove(&self.id);
+
+        let usage = proc::keys()?
+            .into_iter()
+            .map(|key| (key.serial, key.usage))
+            .collect::<HashMap<_, _>>();
+
+        // A keyring is exclusive if all of its references are links from this keyring or other
+        // exclusive keyrings. Keyrings referenced from elsewhere are shared and so is everything
+        // they link to, so drop them until only exclusive keyrings remain.
+        let mut exclusive = parents.keys().copied().collect::<HashSet<_>>();
+        loop {
+            let shared = exclusive
+                .iter()
+                .copied()
+                .filter(|id| {
+                    let links = parents[id]
+                        .iter()
+                        .filter(|parent| **parent == self.id || exclusive.contains(parent))
+                        .count();
+                    usage.get(id).map(|&usage| usage as usize) != Some(links)
+                })
+                .collect::<Vec<_>>();
+            if shared.is_empty() {
+                break;
+            }
+            shared.iter().for_each(|id| {
+                exclusive.remove(id);
+            });
+        }
+
+        exclusive.into_iter().for_each(|id| {
+            // Errors are ignored; the keyring is still unlinked below.
+            let _ = keyctl_invalidate(id);
+        });
+
+        self.clear()
+    }
+
     /// Adds a link to `key` to the keyring.
     ///
-    /// Any link to an existing key with the same description is removed. Requires `write`
-    /// permission on the keyring and `link` permission on the key.
+    /// Any link to an existing key with the same type and description is replaced by the link to
+    /// `key`. Use `try_link_key` to avoid this. Requires `write` permission on the keyring and
+    /// `link` permission on the key.
     pub fn link_key(&mut self, key: &Key) -> Result<()> {
         keyctl_link(key.id, self.id)
     }
 
+    /// Adds a link to `key` to the keyring without replacing existing links.
+    ///
+    /// If the keyring already links to a different key with the same type and description,
+    /// `EEXIST` is returned and the keyring is not changed. Linking a key which is already in the
+    /// keyring succeeds. Note that the check is not atomic with respect to other processes
+    /// linking keys into the keyring. Requires `read` and `write` permission on the keyring,
+    /// `view` permission on its children, and `link` permission on the key.
+    pub fn try_link_key(&mut self, key: &Key) -> Result<()> {
+        let description = key.description()?;
+        let collides = self.read_descriptions()?.into_iter().any(|(child, desc)| {
+            child != *key
+                && desc.type_ == description.type_
+                && desc.description == description.description
+        });
+        if collides {
+            return Err(errno::Errno(libc::EEXIST));
+        }
+        self.link_key(key)
+    }
+
+    /// Adds a link to `key` to the keyring and verifies that the link is present afterwards.
+    ///
+    /// If the link was removed between linking and verifying (e.g., the keyring was cleared
+    /// concurrently), `ENOLINK` is returned. Requires `read` permission on the keyring in addition
+    /// to the permissions required by `link_key`.
+    pub fn link_key_verified(&mut self, key: &Key) -> Result<()> {
+        self.link_key(key)?;
+        self.verify_link(key)
+    }
+
+    pub(crate) fn verify_link(&self, key: &Key) -> Result<()> {
+        if self.contains(key)? {
+            Ok(())
+        } else {
+            Err(errno::Errno(libc::ENOLINK))
+        }
+    }
+
+    /// Whether the keyring has an immediate link to `key`.
+    ///
+    /// Requires `read` permission on the keyring.
+    pub fn contains(&self, key: &Key) -> Result<bool> {
+        self.read_serials().map(|serials| serials.contains(&key.id))
+    }
+
+    /// Whether the keyring has an immediate link to a key of type `K` with the given description.
+    ///
+    /// Unlike searching, this does not recurse into child keyrings nor link any keys. Requires
+    /// `read` permission on the keyring.
+    pub fn contains_key<K, D>(&self, description: D) -> Result<bool>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+    {
+        let description = description.borrow().description();
+        Ok(self
+            .read_descriptions()?
+            .iter()
+            .any(|(_, desc)| desc.type_ == K::name() && desc.description == description))
+    }
+
     /// Removes the link to `key` from the keyring.
     ///
     /// Requires `write` permission on the keyring.
@@ -200,6 +637,31 @@ impl Keyring {
         keyctl_link(keyring.id, self.id)
     }
 
+    /// Removes the links to children of the keyring for which `f` returns `false`.
+    ///
+    /// Child keyrings are passed to `f` as keys as well. Children which are removed (or unlinked)
+    /// by another process before they can be unlinked are skipped. Returns the number of children
+    /// unlinked. Requires `read` and `write` permission on the keyring.
+    pub fn retain<F>(&mut self, mut f: F) -> Result<usize>
+    where
+        F: FnMut(&Key) -> bool,
+    {
+        let mut removed = 0;
+        for serial in self.read_serials()? {
+            let key = Key::new_impl(serial);
+            if f(&key) {
+                continue;
+            }
+            match self.unlink_key(&key) {
+                Ok(()) => removed += 1,
+                // The key is gone (or the link was already removed).
+                Err(errno::Errno(libc::ENOKEY)) | Err(errno::Errno(libc::ENOENT)) => {},
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(removed)
+    }
+
     /// Removes the link to `keyring` from the keyring.
     ///
     /// Requires `write` permission on the keyring.
@@ -207,59 +669,126 @@ impl Keyring {
         keyctl_unlink(keyring.id, self.id)
     }
 
-    fn search_impl<K>(
+    fn search_impl(
         &self,
+        type_: &str,
         description: &str,
         destination: Option<&mut Keyring>,
-    ) -> Result<KeyringSerial>
+    ) -> Result<KeyringSerial> {
+        keyctl_search(self.id, type_, description, destination.map(|dest| dest.id))
+    }
+
+    /// Atomically move `key` from this keyring to `to`.
+    ///
+    /// If `replace` is `false` and `to` already links to a key of the same type and description,
+    /// `EEXIST` is returned and neither keyring is changed. Otherwise, such a key is displaced
+    /// from `to`. Requires `write` permission on both keyrings and `link` permission on the key.
+    pub fn move_key(&mut self, key: &Key, to: &mut Keyring, replace: bool) -> Result<()> {
+        let flags = if replace { 0 } else { KEYCTL_MOVE_EXCL };
+        keyctl_move(key.id, self.id, to.id, flags)
+    }
+
+    /// Move every immediate child of this keyring to `to`.
+    ///
+    /// Each child is moved atomically as with `move_key`, but the migration as a whole is not
+    /// atomic. If `replace` is `false`, children which collide with a key in `to` are skipped and
+    /// left in this keyring. Any other error stops the migration and is returned; children which
+    /// have already been moved are not moved back. Returns the number of children moved.
+    /// Requires `read` and `write` permission on this keyring, `write` permission on `to`, and
+    /// `link` permission on the children.
+    pub fn move_all(&mut self, to: &mut Keyring, replace: bool) -> Result<usize> {
+        let mut moved = 0;
+        for id in self.read_serials()? {
+            match self.move_key(&Key::new_impl(id), to, replace) {
+                Ok(()) => moved += 1,
+                Err(errno::Errno(libc::EEXIST)) if !replace => (),
+                // The child was unlinked after reading the keyring.
+                Err(errno::Errno(libc::ENOENT)) => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(moved)
+    }
+
+    /// Recursively search the keyring for a key with the matching description.
+    ///
+    /// If it is found and a `destination` is given, it is attached to `destination` (if `write`
+    /// permission to `destination` and `link` permission on the key exist) and return it. The
+    /// destination need not be the searched keyring; if `None`, the key is not linked anywhere.
+    /// Requires the `search` permission on the keyring. Any children keyrings without the
+    /// `search` permission are ignored.
+    pub fn search_for_key<'a, K, D, DK>(&self, description: D, destination: DK) -> Result<Key>
     where
         K: KeyType,
+        D: Borrow<K::Description>,
+        DK: Into<Option<&'a mut Keyring>>,
     {
-        keyctl_search(
-            self.id,
+        self.search_impl(
             K::name(),
-            description,
-            destination.map(|dest| dest.id),
+            &description.borrow().description(),
+            destination.into(),
         )
+        .map(Key::new_impl)
     }
 
-    /// Recursively search the keyring for a key with the matching description.
+    /// Recursively search the keyring for a key of type `K` with the matching description.
     ///
-    /// If it is found, it is attached to the keyring (if `write` permission to the keyring and
-    /// `link` permission on the key exist) and return it. Requires the `search` permission on the
-    /// keyring. Any children keyrings without the `search` permission are ignored.
-    pub fn search_for_key<'a, K, D, DK>(&self, description: D, destination: DK) -> Result<Key>
+    /// This is the same as `search_for_key` without attaching the found key to a keyring.
+    /// Requires the `search` permission on the keyring. Any children keyrings without the
+    /// `search` permission are ignored.
+    pub fn search<K, D>(&self, description: D) -> Result<Key>
     where
         K: KeyType,
         D: Borrow<K::Description>,
-        DK: Into<Option<&'a mut Keyring>>,
     {
-        self.search_impl::<K>(&description.borrow().description(), destination.into())
-            .map(Key::new_impl)
+        self.search_for_key::<K, _, _>(description, None)
     }
 
     /// Recursively search the keyring for a keyring with the matching description.
     ///
-    /// If it is found, it is attached to the keyring (if `write` permission to the keyring and
-    /// `link` permission on the found keyring exist) and return it. Requires the `search`
-    /// permission on the keyring. Any children keyrings without the `search` permission are
-    /// ignored.
+    /// If it is found and a `destination` is given, it is attached to `destination` (if `write`
+    /// permission to `destination` and `link` permission on the found keyring exist) and return
+    /// it. If `None`, the found keyring is not linked anywhere. Requires the `search` permission
+    /// on the keyring. Any children keyrings without the `search` permission are ignored.
     pub fn search_for_keyring<'a, D, DK>(&self, description: D, destination: DK) -> Result<Self>
     where
         D: Borrow<<keytypes::Keyring as KeyType>::Description>,
         DK: Into<Option<&'a mut Keyring>>,
     {
-        self.search_impl::<keytypes::Keyring>(
+        self.search_impl(
+            keytypes::Keyring::name(),
             &description.borrow().description(),
             destination.into(),
         )
         .map(Self::new_impl)
     }
 
-    /// Return all immediate children of the keyring.
+    /// Recursively search the keyring for a key with the matching type and description.
     ///
-    /// Requires `read` permission on the keyring.
-    pub fn read(&self) -> Result<(Vec<Key>, Vec<Keyring>)> {
+    /// This is the same as `search_for_key` except that the type is given by name at runtime.
+    /// Returns `EINVAL` if the type name contains a NUL byte.
+    pub fn search_typed(&self, type_: &str, description: &str) -> Result<Key> {
+        self.search_impl(type_, description, None)
+            .map(Key::new_impl)
+    }
+
+    // Read the serials of the immediate children of the keyring.
+    pub(crate) fn read_serials(&self) -> Result<Vec<KeyringSerial>> {
+        parse_serials(&read_impl(self.id)?)
+    }
+
+    // Read the descriptions of the immediate children of the keyring.
+    fn read_descriptions(&self) -> Result<Vec<(Key, Description)>> {
+        self.read_descriptions_impl(false)
+    }
+
+    // Read the descriptions of the immediate children of the keyring, skipping children which
+    // may not be viewed.
+    fn read_viewable_descriptions(&self) -> Result<Vec<(Key, Description)>> {
+        self.read_descriptions_impl(true)
+    }
+
+    fn read_descriptions_impl(&self, skip_hidden: bool) -> Result<Vec<(Key, Description)>> {
         // The `description` check below hides this error code from the kernel.
         if self.id.get() == 0 {
             return Err(errno::Errno(libc::ENOKEY));
@@ -272,50 +801,207 @@ impl Keyring {
             return Err(errno::Errno(libc::ENOTDIR));
         }
 
-        let buffer = read_impl(self.id)?;
-        let keyring_children = {
-            let chunk_size = mem::size_of::<KeyringSerial>();
-            let chunks = buffer.chunks(chunk_size);
-            chunks.map(|chunk| {
-                let bytes = chunk.try_into().map_err(|err| {
-                    error!(
-                        "A keyring did not have the right number of bytes for a child key or \
-                         keyring ID: {}",
-                        err,
-                    );
-                    errno::Errno(libc::EINVAL)
-                })?;
-                let id = i32::from_ne_bytes(bytes);
-                let serial = KeyringSerial::new(id).ok_or_else(|| {
-                    error!("A keyring had a child key or keyring ID of 0");
-                    errno::Errno(libc::EINVAL)
-                })?;
-                Ok(Key::new_impl(serial))
-            })
-        };
+        let keyring_children = self.read_serials()?;
 
-        let mut keys = Vec::new();
-        let mut keyrings = Vec::new();
-        for key in keyring_children {
-            let key = key?;
+        let mut descriptions = Vec::with_capacity(keyring_children.len());
+        for serial in keyring_children {
+            let key = Key::new_impl(serial);
             match key.description() {
-                Ok(description) => {
-                    if description.type_ == keytypes::Keyring::name() {
-                        keyrings.push(Keyring::new_impl(key.id))
-                    } else {
-                        keys.push(key)
-                    }
-                },
+                Ok(description) => descriptions.push((key, description)),
                 // Keys can be invalidated between reading the keyring and
                 // reading the child key's description. If this happens, we get
                 // ENOKEY and just skip that key.
                 Err(errno::Errno(libc::ENOKEY)) => {},
+                Err(errno::Errno(libc::EACCES)) if skip_hidden => {},
                 Err(e) => return Err(e),
             }
         }
+        Ok(descriptions)
+    }
+
+    /// Return all immediate children of the keyring.
+    ///
+    /// This looks up the type of every child. Use `children` to avoid this when only some (or
+    /// none) of the children need to be classified. Requires `read` permission on the keyring.
+    pub fn read(&self) -> Result<(Vec<Key>, Vec<Keyring>)> {
+        let mut keys = Vec::new();
+        let mut keyrings = Vec::new();
+        for (key, description) in self.read_descriptions()? {
+            if description.type_ == keytypes::Keyring::name() {
+                keyrings.push(Keyring::new_impl(key.id))
+            } else {
+                keys.push(key)
+            }
+        }
         Ok((keys, keyrings))
     }
 
+    /// The number of immediate children of the keyring.
+    ///
+    /// Unlike `read`, this does not query each of the children. Returns `ENOTDIR` if this is not
+    /// a keyring. Requires `read` permission on the keyring.
+    pub fn len(&self) -> Result<usize> {
+        if self.description()?.type_ != keytypes::Keyring::name() {
+            return Err(errno::Errno(libc::ENOTDIR));
+        }
+        keyctl_read(self.id, None).map(|size| size / mem::size_of::<KeyringSerial>())
+    }
+
+    /// Whether the keyring has no children.
+    ///
+    /// See `len`.
+    pub fn is_empty(&self) -> Result<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Return the immediate children of the keyring without querying them.
+    ///
+    /// Unlike `read`, the type of each child is not looked up, so only a single system call is
+    /// made. Use `KeyringChild::description` or `KeyringChild::is_keyring` to classify the
+    /// children which are of interest. Requires `read` permission on the keyring.
+    pub fn children(&self) -> Result<Vec<KeyringChild>> {
+        self.read_serials().map(|serials| {
+            serials
+                .into_iter()
+                .map(|id| {
+                    KeyringChild {
+                        id,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Count the immediate children of the keyring by their type.
+    ///
+    /// Children which disappear while the keyring is being read are not counted. Requires `read`
+    /// permission on the keyring.
+    pub fn type_histogram(&self) -> Result<HashMap<String, usize>> {
+        let mut histogram = HashMap::new();
+        for (_, description) in self.read_descriptions()? {
+            *histogram.entry(description.type_).or_insert(0) += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Return the `/proc/keys` entries for the immediate children of the keyring.
+    ///
+    /// Entries are returned in the order the children are linked into the keyring. Children
+    /// which are not listed in `/proc/keys` (e.g., because the process may not view them) are
+    /// skipped. Requires `read` permission on the keyring.
+    pub fn proc_members(&self) -> Result<Vec<ProcKey>> {
+        let serials = self.read_serials()?;
+        let mut entries = proc::keys()?
+            .into_iter()
+            .map(|key| (key.serial, key))
+            .collect::<HashMap<_, _>>();
+        Ok(serials
+            .into_iter()
+            .filter_map(|serial| entries.remove(&serial))
+            .collect())
+    }
+
+    /// Find the path through nested keyrings which a search for a key would take.
+    ///
+    /// Like the kernel, the keys linked directly into a keyring are considered before descending
+    /// into its child keyrings, in link order. The returned path starts with this keyring and
+    /// ends with the keyring which links to the first matching key. Unlike the kernel's search,
+    /// which only needs `search` permission, the path is found by reading keyrings, so this
+    /// requires `read` permission on the keyring and its nested keyrings as well as `view`
+    /// permission on their children. Returns `None` if no matching key is linked within the tree.
+    /// If no match is found but some nested keyring could not be read, `EACCES` is returned
+    /// instead since the key may be hidden within it.
+    pub fn resolve_path<K>(&self, description: &str) -> Result<Option<Vec<KeyringSerial>>>
+    where
+        K: KeyType,
+    {
+        let mut path = Vec::new();
+        let mut skipped = false;
+        if self.resolve_path_impl(K::name(), description, &mut path, &mut skipped)? {
+            Ok(Some(path))
+        } else if skipped {
+            Err(errno::Errno(libc::EACCES))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn resolve_path_impl(
+        &self,
+        type_: &str,
+        description: &str,
+        path: &mut Vec<KeyringSerial>,
+        skipped: &mut bool,
+    ) -> Result<bool> {
+        // The kernel limits the nesting of keyrings during searches.
+        const MAX_SEARCH_DEPTH: usize = 6;
+
+        let children = self.read_descriptions()?;
+        path.push(self.id);
+
+        let found = children
+            .iter()
+            .any(|(_, desc)| desc.type_ == type_ && desc.description == description);
+        if found {
+            return Ok(true);
+        }
+
+        if path.len() < MAX_SEARCH_DEPTH {
+            let keyrings = children
+                .into_iter()
+                .filter(|(_, desc)| desc.type_ == keytypes::Keyring::name())
+                .map(|(key, _)| Keyring::new_impl(key.id));
+            for keyring in keyrings {
+                if path.contains(&keyring.id) {
+                    continue;
+                }
+                match keyring.resolve_path_impl(type_, description, path, skipped) {
+                    Ok(true) => return Ok(true),
+                    Ok(false) | Err(errno::Errno(libc::ENOKEY)) => {},
+                    Err(errno::Errno(libc::EACCES)) => *skipped = true,
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        path.pop();
+        Ok(false)
+    }
+
+    /// Iterate over the keyring tree depth-first.
+    ///
+    /// Each item is a child along with its depth (immediate children have a depth of 0). Child
+    /// keyrings are yielded before their contents. Child keyrings which cannot be read (e.g.,
+    /// due to missing `read` or `search` permission) are yielded, but not descended into.
+    /// Children which may not be viewed are skipped. Each keyring is only descended into once, so
+    /// links back to an ancestor do not loop forever. Requires `read` permission on the keyring;
+    /// errors reading the keyring itself are returned.
+    pub fn iter(&self) -> Result<KeyringIter> {
+        KeyringIter::new(self)
+    }
+
+    /// Find all keys of type `K` within the keyring tree.
+    ///
+    /// The tree is walked as with `iter` and each key is returned once, even if it is linked into
+    /// multiple keyrings. Requires `read` permission on the keyring. Children which may not be
+    /// viewed and child keyrings which cannot be read are skipped.
+    pub fn find_all<K>(&self) -> Result<Vec<Key>>
+    where
+        K: KeyType,
+    {
+        let mut iter = self.iter()?;
+        let mut found = HashSet::new();
+        let mut keys = Vec::new();
+
+        while let Some((key, description, _)) = iter.next_entry() {
+            if description.type_ == K::name() && found.insert(key.id) {
+                keys.push(key);
+            }
+        }
+
+        Ok(keys)
+    }
+
     /// Attach the persistent keyring for the current user to the current keyring.
     ///
     /// If one does not exist, it will be created. Requires `write` permission on the keyring.
@@ -323,6 +1009,17 @@ impl Keyring {
         keyctl_get_persistent(!0, self.id).map(Self::new_impl)
     }
 
+    /// Attach the persistent keyring for the user `uid` to the current keyring.
+    ///
+    /// If one does not exist, it will be created. Attaching the persistent keyring of another user
+    /// requires the `CAP_SETUID` capability (or `uid` must be the real, effective, or saved user ID
+    /// of the current process). The persistent keyring expires after a period of not being
+    /// accessed (3 days by default; see `/proc/sys/kernel/keys/persistent_keyring_expiry`) and
+    /// each access resets the timer. Requires `write` permission on the keyring.
+    pub fn attach_persistent_for(&mut self, uid: libc::uid_t) -> Result<Self> {
+        keyctl_get_persistent(uid, self.id).map(Self::new_impl)
+    }
+
     /// Adds a key of a specific type to the keyring.
     ///
     /// If a key with the same description already exists and has the `update` permission, it will
@@ -337,6 +1034,134 @@ impl Keyring {
             .map(Key::new_impl)
     }
 
+    /// Adds a key of a specific type to the keyring and immediately sets its permissions.
+    ///
+    /// The kernel does not accept permissions when adding a key, so the key briefly has the
+    /// default permissions before they are replaced with `perms`. This method minimizes that
+    /// window, but it still exists. If setting the permissions fails, the key is left in the
+    /// keyring with its previous permissions and the error is returned. Requires `write`
+    /// permission on the keyring and `setattr` permission on the key.
+    pub fn add_key_with_permissions<K, D, P>(
+        &mut self,
+        description: D,
+        payload: P,
+        perms: Permission,
+    ) -> Result<Key>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        let mut key = self.add_key::<K, _, _>(description, payload)?;
+        key.set_permissions(perms)?;
+        Ok(key)
+    }
+
+    /// Adds a key of a specific type to the keyring with permissions only for its possessors.
+    ///
+    /// The key is given all possessor permissions and `view` permission for its owner; the
+    /// group and other permissions are removed. See `add_key_with_permissions`.
+    pub fn add_key_locked<K, D, P>(&mut self, description: D, payload: P) -> Result<Key>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        self.add_key_with_permissions::<K, _, _>(
+            description,
+            payload,
+            Permission::POSSESSOR_ALL | Permission::USER_VIEW,
+        )
+    }
+
+    /// Adds a key of a specific type to the keyring unless one with the same description exists.
+    ///
+    /// If the keyring already links to a key of type `K` with the same description, `EEXIST` is
+    /// returned and the keyring is not changed. Note that the check is not atomic with respect to
+    /// other processes adding keys to the keyring; a keyring restricted (see `restrict`) to
+    /// trusted writers gives stronger guarantees. Requires `read` and `write` permission on the
+    /// keyring.
+    pub fn try_add_key<K, D, P>(&mut self, description: D, payload: P) -> Result<Key>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        let description = description.borrow();
+        if self.contains_key::<K, _>(description)? {
+            return Err(errno::Errno(libc::EEXIST));
+        }
+        self.add_key::<K, _, _>(description, payload)
+    }
+
+    /// Create a builder for adding a key of type `K` to a keyring.
+    pub fn key_builder<'a, K>() -> KeyBuilder<'a, K>
+    where
+        K: KeyType,
+    {
+        KeyBuilder::new()
+    }
+
+    /// Adds a key of a specific type to the keyring and reports whether it was created.
+    ///
+    /// This is the same as `add_key`, but also indicates whether an existing key in the keyring
+    /// was updated or a new key was created. This is determined by looking for a key with the
+    /// same type and description in the keyring before adding the key, so a key linked into the
+    /// keyring concurrently by another process may be misreported. Requires `read` and `write`
+    /// permission on the keyring.
+    pub fn add_key_verbose<K, D, P>(
+        &mut self,
+        description: D,
+        payload: P,
+    ) -> Result<(Key, KeyOutcome)>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        let description = description.borrow();
+        let desc = description.description();
+        let existing = self
+            .read_descriptions()?
+            .into_iter()
+            .find(|(_, existing)| existing.type_ == K::name() && existing.description == desc)
+            .map(|(key, _)| key.id);
+        let id = self.add_key_impl::<K>(description, payload.borrow())?;
+        let outcome = if existing == Some(id) {
+            KeyOutcome::Updated
+        } else {
+            KeyOutcome::Created
+        };
+        Ok((Key::new_impl(id), outcome))
+    }
+
+    /// Adds a key to the keyring, reusing an existing key handle if available.
+    ///
+    /// If `existing` is given, its payload is updated and it is linked into the keyring
+    /// (`description` is not used in this case). Otherwise, this is the same as `add_key`.
+    /// Requires `write` permission on the keyring and, for an existing key, `write` and `link`
+    /// permissions on the key.
+    pub fn adopt_or_add<K, D, P>(
+        &mut self,
+        description: D,
+        payload: P,
+        existing: Option<&Key>,
+    ) -> Result<Key>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        if let Some(key) = existing {
+            let mut key = key.clone();
+            key.update::<K, _>(payload)?;
+            self.link_key(&key)?;
+            Ok(key)
+        } else {
+            self.add_key::<K, _, _>(description, payload)
+        }
+    }
+
     /// Monomorphization of adding a key.
     fn add_key_impl<K>(
         &mut self,
@@ -354,10 +1179,63 @@ impl Keyring {
         )
     }
 
+    /// Promote the payload of the `next_desc` user key to the `current_desc` user key.
+    ///
+    /// Only the immediate children of the keyring are considered; `ENOKEY` is returned if no
+    /// `next_desc` key is linked into the keyring. The `current_desc` key is updated (or created
+    /// in the keyring) with the payload of the `next_desc` key, which is unlinked from the keyring
+    /// afterwards if `remove_next` is set. The payload is cleared from memory afterwards.
+    /// Requires `read` and `write` permission on the keyring, `view` permission on its children,
+    /// and `read` permission on the `next_desc` key.
+    pub fn rotate(
+        &mut self,
+        current_desc: &str,
+        next_desc: &str,
+        remove_next: bool,
+    ) -> Result<Key> {
+        let next = self
+            .read_descriptions()?
+            .into_iter()
+            .find(|(_, desc)| desc.type_ == keytypes::User::name() && desc.description == next_desc)
+            .map(|(key, _)| key)
+            .ok_or(errno::Errno(libc::ENOKEY))?;
+        let payload = next.read_secret()?;
+        let current = self.add_key::<keytypes::User, _, _>(current_desc, &payload[..])?;
+        if remove_next {
+            self.unlink_key(&next)?;
+        }
+        Ok(current)
+    }
+
+    /// Adds a key of a specific type with key material generated by the kernel.
+    ///
+    /// The size of the key is given in bits and must be a multiple of 8, otherwise `EINVAL` is
+    /// returned. Requires `write` permission.
+    pub fn generate_key<K, D>(
+        &mut self,
+        description: D,
+        bits: usize,
+        options: &K::Options,
+    ) -> Result<Key>
+    where
+        K: GeneratableKeyType,
+        D: Borrow<K::Description>,
+    {
+        let len = bits / 8;
+        if len * 8 != bits {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+        let payload = K::generate_payload(len, options);
+        self.add_key_impl::<K>(description.borrow(), &payload)
+            .map(Key::new_impl)
+    }
+
     /// Adds a keyring to the current keyring.
     ///
     /// If a keyring with the same description already, the link to the old keyring will be
     /// removed. Requires `write` permission on the keyring.
+    ///
+    /// Keyrings have no payload; a null pointer with a zero length is passed to the kernel.
     pub fn add_keyring<D>(&mut self, description: D) -> Result<Self>
     where
         D: Borrow<<keytypes::Keyring as KeyType>::Description>,
@@ -397,6 +1275,20 @@ impl Keyring {
         keyctl_setperm(self.id, perms.bits())
     }
 
+    /// Grant additional permissions to possessors of the keyring.
+    ///
+    /// The current permissions are read and `perms` are added to them; the user, group, and
+    /// other permissions are not changed. Returns `EINVAL` if `perms` contains non-possessor
+    /// permissions. Note that the permissions are read and set in separate operations. Requires
+    /// the `view` and `setattr` permissions on the keyring.
+    pub fn grant_possessor(&mut self, perms: Permission) -> Result<()> {
+        if !Permission::POSSESSOR_ALL.contains(perms) {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+        let current = self.description()?.perms;
+        self.set_permissions(current | perms)
+    }
+
     #[cfg(test)]
     pub(crate) fn set_permissions_raw(&mut self, perms: KeyPermissions) -> Result<()> {
         keyctl_setperm(self.id, perms)
@@ -407,7 +1299,7 @@ impl Keyring {
     /// Requires the `setattr` permission on the keyring and the SysAdmin capability to change it to
     /// anything other than the current user.
     pub fn restrict_all(&mut self) -> Result<()> {
-        keyctl_restrict_keyring(self.id, Restriction::AllLinks)
+        self.restrict(&Restriction::None)
     }
 
     /// Restrict links into the keyring.
@@ -419,96 +1311,432 @@ impl Keyring {
         K: RestrictableKeyType,
         R: Borrow<K::Restriction>,
     {
-        keyctl_restrict_keyring(
-            self.id,
+        self.restrict(&Restriction::by_type::<K, _>(restriction))
+    }
+
+    /// Apply a restriction to links into the keyring.
+    ///
+    /// `Restriction::None` passes no key type to the kernel and rejects all further links. Key
+    /// types which do not implement restrictions (currently only `asymmetric` does) are rejected
+    /// with `ENOENT`; in particular, there is no restriction which only accepts keyrings as
+    /// children. Instead, link the desired keyrings first and then use `Restriction::None`.
+    /// Restrictions may not be changed once applied.
+    ///
+    /// Requires the `setattr` permission on the keyring and the SysAdmin capability to change it to
+    /// anything other than the current user.
+    pub fn restrict(&mut self, restriction: &Restriction) -> Result<()> {
+        let restriction = match restriction {
+            Restriction::None => keyutils_raw::Restriction::AllLinks,
             Restriction::ByType {
-                type_: K::name(),
-                restriction: &restriction.borrow().restriction(),
+                type_,
+                restriction,
+            } => {
+                keyutils_raw::Restriction::ByType {
+                    type_,
+                    restriction,
+                }
             },
-        )
+        };
+        keyctl_restrict_keyring(self.id, restriction)
     }
 
-    fn description_raw(&self) -> Result<String> {
-        // Get the size of the description.
-        let mut sz = keyctl_describe(self.id, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_describe(self.id, Some(write_buffer))?;
-
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
-
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
-        }
-        // Remove 1 from the size for the trailing NUL the kernel adds.
-        buffer.truncate(sz.saturating_sub(1));
-        // The kernel guarantees that we get ASCII data from this.
-        let str_slice = str::from_utf8(&buffer[..]).unwrap();
-        Ok(str_slice.to_owned())
+    /// Retrieve the description of the keyring as given by the kernel.
+    ///
+    /// This is the `;`-separated string which `description` parses. It includes any fields which
+    /// the parser does not know about. Returns `EILSEQ` if the description is not valid UTF-8.
+    /// Requires `view` permission on the keyring.
+    pub fn describe_raw(&self) -> Result<String> {
+        read_nul_terminated(|write_buffer| keyctl_describe(self.id, write_buffer))
     }
 
     /// Retrieve metadata about the keyring.
     ///
-    /// # Panics
-    ///
-    /// If the kernel returns malformed data, the the parser will panic.
+    /// If the kernel returns malformed data, `EINVAL` is returned.
     pub fn description(&self) -> Result<Description> {
-        self.description_raw()
-            .and_then(|desc| Description::parse(&desc).ok_or(errno::Errno(libc::EINVAL)))
+        let desc = self.describe_raw()?;
+        Description::parse(&desc).map_err(|err| {
+            error!("Failed to parse a key description ({}): {}", err, desc);
+            errno::Errno(libc::EINVAL)
+        })
     }
 
     /// Set an expiration timer on the keyring to `timeout`.
     ///
     /// Any partial seconds are ignored. A timeout of 0 means "no expiration". Requires the
     /// `setattr` permission on the keyring.
+    #[deprecated(since = "0.5.0", note = "use `set_timeout_duration` instead")]
     pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
         keyctl_set_timeout(self.id, timeout.as_secs() as TimeoutSeconds)
     }
 
+    /// Set an expiration timer on the keyring to `timeout`.
+    ///
+    /// `None` removes any expiration timer. Partial seconds are rounded up (so that short
+    /// timeouts do not remove the timer instead) and timeouts too large for the kernel saturate.
+    /// Requires the `setattr` permission on the keyring.
+    pub fn set_timeout_duration(&mut self, timeout: Option<Duration>) -> Result<()> {
+        keyctl_set_timeout(self.id, timeout_seconds(timeout))
+    }
+
+    /// The time remaining until the keyring expires.
+    ///
+    /// See `Key::timeout_remaining`.
+    pub fn timeout_remaining(&self) -> Result<Option<Duration>> {
+        Ok(match proc::key(self.id)?.timeout {
+            ProcKeyTimeout::Permanent => None,
+            ProcKeyTimeout::Expired => Some(Duration::from_secs(0)),
+            ProcKeyTimeout::Remaining(remaining) => Some(remaining),
+        })
+    }
+
     /// The security context of the keyring. Depends on the security manager loaded into the kernel
     /// (e.g., SELinux or AppArmor).
     pub fn security(&self) -> Result<String> {
-        // Get the size of the description.
-        let mut sz = keyctl_get_security(self.id, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
+        read_nul_terminated(|write_buffer| keyctl_get_security(self.id, write_buffer))
+    }
+
+    /// Invalidates the keyring and schedules it for removal. Requires the `search` permission on
+    /// the keyring.
+    pub fn invalidate(self) -> Result<()> {
+        keyctl_invalidate(self.id)
+    }
+
+    /// Clears the contents of the keyring and then invalidates it.
+    ///
+    /// The keyring is invalidated even if clearing it fails (e.g., due to missing `write`
+    /// permission). The first error which occurs is returned. Requires the `write` and `search`
+    /// permissions on the keyring.
+    pub fn destroy(mut self) -> Result<()> {
+        let cleared = self.clear();
+        let invalidated = self.invalidate();
+        cleared.and(invalidated)
+    }
+}
+
+/// A builder for adding a key along with its attributes.
+///
+/// Obtained from `Keyring::key_builder`. The kernel cannot add a key with attributes atomically,
+/// so the key is added and then its timeout and permissions are set (in that order, so that
+/// the permissions may drop `setattr`).
+#[derive(Debug)]
+pub struct KeyBuilder<'a, K>
+where
+    K: KeyType,
+{
+    description: Option<&'a K::Description>,
+    payload: Option<&'a K::Payload>,
+    permissions: Option<Permission>,
+    timeout: Option<Duration>,
+}
+
+impl<'a, K> KeyBuilder<'a, K>
+where
+    K: KeyType,
+{
+    fn new() -> Self {
+        KeyBuilder {
+            description: None,
+            payload: None,
+            permissions: None,
+            timeout: None,
+        }
+    }
+
+    /// The description of the key.
+    pub fn description(mut self, description: &'a K::Description) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// The payload of the key.
+    pub fn payload(mut self, payload: &'a K::Payload) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+
+    /// The permissions to set on the key.
+    pub fn permissions(mut self, permissions: Permission) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// The expiration timeout of the key.
+    ///
+    /// See `Key::set_timeout_duration`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Add the key to `keyring` and apply its attributes.
+    ///
+    /// Returns `EINVAL` if the description or payload is missing. If setting an attribute fails,
+    /// the key is left in the keyring. Requires `write` permission on the keyring and `setattr`
+    /// permission on the key if any attributes are given.
+    pub fn add_to(self, keyring: &mut Keyring) -> Result<Key> {
+        let description = self.description.ok_or(errno::Errno(libc::EINVAL))?;
+        let payload = self.payload.ok_or(errno::Errno(libc::EINVAL))?;
+        let mut key = keyring.add_key::<K, _, _>(description, payload)?;
+        if let Some(timeout) = self.timeout {
+            key.set_timeout_duration(Some(timeout))?;
+        }
+        if let Some(permissions) = self.permissions {
+            key.set_permissions(permissions)?;
+        }
+        Ok(key)
+    }
+}
+
+/// Whether adding a key created a new key or updated an existing one.
+///
+/// Returned by `Keyring::add_key_verbose`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOutcome {
+    /// A new key was created.
+    Created,
+    /// An existing key was updated.
+    Updated,
+}
+
+/// An immediate child of a keyring whose type has not been looked up.
+///
+/// Obtained from `Keyring::children`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyringChild {
+    id: KeyringSerial,
+}
+
+impl KeyringChild {
+    /// The serial number of the child.
+    pub fn serial(&self) -> KeyringSerial {
+        self.id
+    }
+
+    /// Retrieve metadata about the child.
+    ///
+    /// Fails with `ENOKEY` if the child has been removed since the keyring was read.
+    pub fn description(&self) -> Result<Description> {
+        Keyring::new_impl(self.id).description()
+    }
+
+    /// Whether the child is a keyring.
+    pub fn is_keyring(&self) -> Result<bool> {
+        self.description()
+            .map(|desc| desc.type_ == keytypes::Keyring::name())
+    }
+
+    /// A handle to the child as a key.
+    pub fn into_key(self) -> Key {
+        Key::new_impl(self.id)
+    }
+
+    /// A handle to the child as a keyring.
+    ///
+    /// Returns `ENOTDIR` if the child is not a keyring.
+    pub fn into_keyring(self) -> Result<Keyring> {
+        if self.is_keyring()? {
+            Ok(Keyring::new_impl(self.id))
+        } else {
+            Err(errno::Errno(libc::ENOTDIR))
+        }
+    }
+}
+
+/// A node in a keyring tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyringNode {
+    /// A key.
+    Key(Key),
+    /// A nested keyring.
+    Keyring(Keyring),
+}
+
+/// A depth-first iterator over a keyring tree.
+///
+/// Created by `Keyring::iter`.
+#[derive(Debug)]
+pub struct KeyringIter {
+    stack: Vec<vec::IntoIter<(Key, Description)>>,
+    visited: HashSet<KeyringSerial>,
+}
+
+impl KeyringIter {
+    fn new(keyring: &Keyring) -> Result<Self> {
+        let children = keyring.read_viewable_descriptions()?;
+        let mut visited = HashSet::new();
+        visited.insert(keyring.id);
+        Ok(KeyringIter {
+            stack: vec![children.into_iter()],
+            visited,
+        })
+    }
+
+    fn descend(&mut self, keyring: &Keyring) {
+        if !self.visited.insert(keyring.id) {
+            return;
+        }
+
+        // Keyrings which cannot be read are skipped rather than ending the walk.
+        if let Ok(children) = keyring.read_viewable_descriptions() {
+            self.stack.push(children.into_iter());
+        }
+    }
+
+    // Get the next child in the walk along with its description and depth.
+    fn next_entry(&mut self) -> Option<(Key, Description, usize)> {
         loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_get_security(self.id, Some(write_buffer))?;
+            let depth = self.stack.len().checked_sub(1)?;
+            let (key, description) = match self.stack[depth].next() {
+                Some(child) => child,
+                None => {
+                    self.stack.pop();
+                    continue;
+                },
+            };
+
+            if description.type_ == keytypes::Keyring::name() {
+                self.descend(&Keyring::new_impl(key.id));
+            }
+            return Some((key, description, depth));
+        }
+    }
+}
+
+impl Iterator for KeyringIter {
+    type Item = (KeyringNode, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, description, depth) = self.next_entry()?;
+        let node = if description.type_ == keytypes::Keyring::name() {
+            KeyringNode::Keyring(Keyring::new_impl(key.id))
+        } else {
+            KeyringNode::Key(key)
+        };
+        Some((node, depth))
+    }
+}
+
+/// A restriction on the keys which may be linked into a keyring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum Restriction {
+    /// Reject all further links into the keyring.
+    ///
+    /// No key type is given to the kernel for this restriction. Note that this differs from the
+    /// asymmetric key type's "builtin trusted" restriction which still accepts keys signed by a
+    /// trusted key. Adding or linking keys into the keyring fails with `EPERM`.
+    None,
+    /// Only allow keys which pass the given restriction of a key type.
+    ByType {
+        /// The name of the key type which implements the restriction.
+        type_: Cow<'static, str>,
+        /// The restriction string.
+        restriction: String,
+    },
+}
+
+impl Restriction {
+    /// A restriction using the restriction scheme of a key type.
+    pub fn by_type<K, R>(restriction: R) -> Self
+    where
+        K: RestrictableKeyType,
+        R: Borrow<K::Restriction>,
+    {
+        Restriction::ByType {
+            type_: K::name().into(),
+            restriction: restriction.borrow().restriction().into_owned(),
+        }
+    }
+}
+
+/// Representation of a kernel key.
+///
+/// Like `Keyring`, a `Key` is `Send` and `Sync`, but whether the key is possessed depends on the
+/// keyrings of the calling thread. See the thread safety notes on `Keyring`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key {
+    id: KeyringSerial,
+}
+
+/// A view of a kernel key which does not allow modifying it.
+///
+/// Obtained from `Key::read_only`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadOnlyKey {
+    key: Key,
+}
+
+impl ReadOnlyKey {
+    /// The serial of the key.
+    pub fn serial(&self) -> KeyringSerial {
+        self.key.id
+    }
+
+    /// Retrieve metadata about the key.
+    ///
+    /// If the kernel returns malformed data, `EINVAL` is returned.
+    pub fn description(&self) -> Result<Description> {
+        self.key.description()
+    }
+
+    /// The security context of the key.
+    ///
+    /// Depends on the security manager loaded into the kernel (e.g., SELinux or AppArmor).
+    pub fn security(&self) -> Result<String> {
+        self.key.security()
+    }
+
+    /// Read the payload of the key. Requires `read` permissions on the key.
+    ///
+    /// If the payload keeps growing while it is being read, `EAGAIN` is returned.
+    pub fn read(&self) -> Result<Vec<u8>> {
+        self.key.read()
+    }
+
+    /// The size of the payload of the key. Requires `read` permissions on the key.
+    pub fn payload_len(&self) -> Result<usize> {
+        keyctl_read(self.key.id, None)
+    }
+}
+
+/// The payload of a key which is cleared from memory when dropped.
+///
+/// Obtained from `Key::read_secret`. The contents are not shown in the `Debug` output.
+pub struct Secret {
+    data: Vec<u8>,
+}
+
+impl Secret {
+    /// The payload.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
 
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
+impl ops::Deref for Secret {
+    type Target = [u8];
 
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
-        }
-        // Remove 1 from the size for the trailing NUL the kernel adds.
-        buffer.truncate(sz.saturating_sub(1));
-        // The kernel guarantees that we get ASCII data from this.
-        let str_slice = str::from_utf8(&buffer[..]).unwrap();
-        Ok(str_slice.to_owned())
+    fn deref(&self) -> &Self::Target {
+        &self.data
     }
+}
 
-    /// Invalidates the keyring and schedules it for removal. Requires the `search` permission on
-    /// the keyring.
-    pub fn invalidate(self) -> Result<()> {
-        keyctl_invalidate(self.id)
+impl AsRef<[u8]> for Secret {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
     }
 }
 
-/// Representation of a kernel key.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Key {
-    id: KeyringSerial,
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Secret").field(&keytypes::Redacted).finish()
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        zeroize(&mut self.data);
+    }
 }
 
 /// Structure to store results from a query on optional feature support for a key.
@@ -541,8 +1769,33 @@ impl KeySupportInfo {
     }
 }
 
+/// The encoding and hash combinations which a key accepts.
+#[derive(Debug, Clone, Default)]
+pub struct AlgoSupport {
+    /// The accepted combinations along with the support information reported for each.
+    pub supported: Vec<(PublicKeyOptions, KeySupportInfo)>,
+}
+
+impl AlgoSupport {
+    /// Whether the given encoding and hash combination is supported by the key.
+    pub fn supports(&self, encoding: &KeyctlEncoding, hash: &KeyctlHash) -> bool {
+        self.info(encoding, hash).is_some()
+    }
+
+    /// The support information for a given encoding and hash combination.
+    pub fn info(&self, encoding: &KeyctlEncoding, hash: &KeyctlHash) -> Option<KeySupportInfo> {
+        self.supported
+            .iter()
+            .find(|(options, _)| {
+                options.encoding.as_ref().map(KeyctlEncoding::encoding) == Some(encoding.encoding())
+                    && options.hash.as_ref().map(KeyctlHash::hash) == Some(hash.hash())
+            })
+            .map(|&(_, info)| info)
+    }
+}
+
 /// Encodings supported by the kernel.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 // #[non_exhaustive]
 pub enum KeyctlEncoding {
     /// The RSASSA-PKCS1-v1.5 encoding.
@@ -567,10 +1820,17 @@ impl KeyctlEncoding {
             KeyctlEncoding::OtherEncoding(ref s) => &s,
         }
     }
+
+    // Encodings to probe for support. Both PKCS1 variants use the same name in the kernel.
+    const PROBES: &'static [KeyctlEncoding] = &[
+        KeyctlEncoding::RsassaPkcs1V15,
+        KeyctlEncoding::RsassaPss,
+        KeyctlEncoding::RsaesOaep,
+    ];
 }
 
 /// Hashes supported by the kernel.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 // #[non_exhaustive]
 pub enum KeyctlHash {
     /// The MD4 hash.
@@ -637,6 +1897,28 @@ impl KeyctlHash {
             KeyctlHash::OtherEncoding(ref s) => &s,
         }
     }
+
+    // Hashes to probe for support.
+    const PROBES: &'static [KeyctlHash] = &[
+        KeyctlHash::Md4,
+        KeyctlHash::Md5,
+        KeyctlHash::Sha1,
+        KeyctlHash::Sha224,
+        KeyctlHash::Sha256,
+        KeyctlHash::Sha384,
+        KeyctlHash::Sha512,
+        KeyctlHash::RipeMd128,
+        KeyctlHash::RipeMd160,
+        KeyctlHash::RipeMd256,
+        KeyctlHash::RipeMd320,
+        KeyctlHash::Wp256,
+        KeyctlHash::Wp384,
+        KeyctlHash::Wp512,
+        KeyctlHash::Tgr128,
+        KeyctlHash::Tgr160,
+        KeyctlHash::Tgr192,
+        KeyctlHash::Sm3_256,
+    ];
 }
 
 /// Options for output from public key functions (encryption, decryption, signing, and verifying).
@@ -661,6 +1943,10 @@ impl PublicKeyOptions {
     }
 }
 
+// Many methods here delegate to the `Keyring` implementation of the same operation. This is
+// sound because the underlying `keyctl` operations act on any key by serial; only the operations
+// which read or modify the contents of a keyring are specific to keyrings and those are not
+// exposed on `Key`.
 impl Key {
     /// Instantiate a key from an ID.
     ///
@@ -673,16 +1959,59 @@ impl Key {
         Self::new_impl(id)
     }
 
-    fn new_impl(id: KeyringSerial) -> Self {
+    /// Create a handle for an existing key from its serial number.
+    ///
+    /// The serial must be positive and refer to a key which may be viewed, otherwise the error
+    /// from the kernel is returned. The handle does not take ownership of the key in any way; it
+    /// is only a reference to it by serial number.
+    pub fn from_serial(serial: i32) -> Result<Self> {
+        let key = Self::new_impl(checked_serial(serial)?);
+        key.revalidate()?;
+        Ok(key)
+    }
+
+    pub(crate) fn new_impl(id: KeyringSerial) -> Self {
         Key {
             id,
         }
     }
 
-    pub(crate) fn serial(&self) -> KeyringSerial {
+    /// A keyring handle for the key if it is a keyring.
+    ///
+    /// Returns `None` if the key is of another type. Requires `view` permission on the key.
+    pub fn as_keyring(&self) -> Result<Option<Keyring>> {
+        let desc = self.description()?;
+        Ok(if desc.type_ == keytypes::Keyring::name() {
+            Some(Keyring::new_impl(self.id))
+        } else {
+            None
+        })
+    }
+
+    /// The serial number of the key.
+    ///
+    /// This is the ID used by the kernel for the key, e.g., in `/proc/keys` and by `keyctl`.
+    pub fn serial(&self) -> KeyringSerial {
         self.id
     }
 
+    /// Get a view of the key which only allows reading it.
+    pub fn read_only(&self) -> ReadOnlyKey {
+        ReadOnlyKey {
+            key: self.clone(),
+        }
+    }
+
+    /// Whether the handle refers to the same kernel key as `other`.
+    pub fn same_key(&self, other: &Key) -> bool {
+        self.id == other.id
+    }
+
+    /// Whether the handle refers to the same kernel object as the `keyring` handle.
+    pub fn refers_to(&self, keyring: &Keyring) -> bool {
+        self.id == keyring.id
+    }
+
     /// Requests a key with the given type and description by searching the thread, process, and
     /// session keyrings.
     ///
@@ -699,7 +2028,8 @@ impl Key {
         I: Into<Option<&'s str>>,
         T: Into<Option<TargetKeyring<'a>>>,
     {
-        request_impl::<K>(
+        request_impl(
+            K::name(),
             &description.borrow().description(),
             info.into().as_ref().copied(),
             target.into().map(TargetKeyring::serial),
@@ -707,6 +2037,29 @@ impl Key {
         .map(Self::new_impl)
     }
 
+    /// Requests a key with the given type name and description.
+    ///
+    /// This is the same as `request` except that the type is given by name at runtime. Returns
+    /// `EINVAL` if the type name contains a NUL byte.
+    pub fn request_typed<'s, 'a, I, T>(
+        type_: &str,
+        description: &str,
+        info: I,
+        target: T,
+    ) -> Result<Self>
+    where
+        I: Into<Option<&'s str>>,
+        T: Into<Option<TargetKeyring<'a>>>,
+    {
+        request_impl(
+            type_,
+            description,
+            info.into().as_ref().copied(),
+            target.into().map(TargetKeyring::serial),
+        )
+        .map(Self::new_impl)
+    }
+
     /// Determine whether the key is of a specific implementation or not.
     pub fn is_keytype<K>(&self) -> Result<bool>
     where
@@ -754,6 +2107,13 @@ impl Key {
         Keyring::new_impl(self.id).set_permissions(perms)
     }
 
+    /// Grant additional permissions to possessors of the key.
+    ///
+    /// See `Keyring::grant_possessor`.
+    pub fn grant_possessor(&mut self, perms: Permission) -> Result<()> {
+        Keyring::new_impl(self.id).grant_possessor(perms)
+    }
+
     #[cfg(test)]
     pub(crate) fn set_permissions_raw(&mut self, perms: KeyPermissions) -> Result<()> {
         Keyring::new_impl(self.id).set_permissions_raw(perms)
@@ -761,24 +2121,212 @@ impl Key {
 
     /// Retrieve metadata about the key.
     ///
-    /// # Panics
-    ///
-    /// If the kernel returns malformed data, the parser will panic.
+    /// If the kernel returns malformed data, `EINVAL` is returned.
     pub fn description(&self) -> Result<Description> {
         Keyring::new_impl(self.id).description()
     }
 
+    /// Retrieve the description of the key as given by the kernel.
+    ///
+    /// See `Keyring::describe_raw`.
+    pub fn describe_raw(&self) -> Result<String> {
+        Keyring::new_impl(self.id).describe_raw()
+    }
+
+    /// Check that the key is still accessible through this handle.
+    ///
+    /// This is useful after the credentials of the process have changed (e.g., via `setuid` or
+    /// `setgid`) since possession of a key may be lost. Returns `EACCES` if the key exists but
+    /// may no longer be viewed (e.g., possession or group membership has been lost) and
+    /// `ENOKEY`, `EKEYREVOKED`, or `EKEYEXPIRED` if the key itself is gone.
+    pub fn revalidate(&self) -> Result<()> {
+        keyctl_describe(self.id, None).map(|_| ())
+    }
+
+    /// Whether the key still exists and has not been revoked or expired.
+    ///
+    /// This is a single system call like `revalidate`, but `ENOKEY`, `EKEYREVOKED`, and
+    /// `EKEYEXPIRED` are mapped to `false`. Other errors (e.g., `EACCES` if the key may no longer
+    /// be viewed) are returned.
+    pub fn is_valid(&self) -> Result<bool> {
+        match self.revalidate() {
+            Ok(()) => Ok(true),
+            Err(errno::Errno(libc::ENOKEY))
+            | Err(errno::Errno(libc::EKEYREVOKED))
+            | Err(errno::Errno(libc::EKEYEXPIRED)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Watch the key for changes.
+    ///
+    /// Returns `EOPNOTSUPP` if the kernel does not support key notifications. Requires the
+    /// `view` permission on the key.
+    pub fn watch(&self) -> Result<KeyWatch> {
+        KeyWatch::new(self)
+    }
+
     /// Read the payload of the key. Requires `read` permissions on the key.
+    ///
+    /// If the payload keeps growing while it is being read, `EAGAIN` is returned.
     pub fn read(&self) -> Result<Vec<u8>> {
         read_impl(self.id)
     }
 
+    /// Read the payload of the key into a buffer which is cleared when dropped.
+    ///
+    /// If the payload grows while it is being read, intermediate buffers are cleared before
+    /// being freed as well. Requires `read` permissions on the key.
+    pub fn read_secret(&self) -> Result<Secret> {
+        for _ in 0..MAX_READ_ATTEMPTS {
+            let mut secret = Secret {
+                data: vec![0; keyctl_read(self.id, None)?],
+            };
+            let sz = self.read_into_locked(&mut secret.data)?;
+            if sz <= secret.data.len() {
+                secret.data.truncate(sz);
+                return Ok(secret);
+            }
+            // The payload grew; `secret` is cleared when it is dropped here.
+        }
+        Err(errno::Errno(libc::EAGAIN))
+    }
+
+    /// Read the payload of the key and interpret it as a payload of type `K`.
+    ///
+    /// The type of the key is not checked; the payload is interpreted as if the key were of type
+    /// `K`. Requires `read` permissions on the key.
+    pub fn read_as<K>(&self) -> Result<K::Output>
+    where
+        K: ReadableKeyType,
+    {
+        K::parse_payload(&self.read()?)
+    }
+
+    /// Read the payload of the key as a UTF-8 string.
+    ///
+    /// Returns `EILSEQ` if the payload is not valid UTF-8. Note that this always fails for `logon`
+    /// keys since their payloads may not be read from userspace. Requires `read` permissions on
+    /// the key.
+    pub fn read_string(&self) -> Result<String> {
+        String::from_utf8(self.read()?).map_err(|_| errno::Errno(libc::EILSEQ))
+    }
+
+    /// Read the payload of the key into `buffer`, reusing its allocation.
+    ///
+    /// Any existing contents of `buffer` are replaced. Returns the size of the payload. Requires
+    /// `read` permissions on the key.
+    pub fn read_into(&self, buffer: &mut Vec<u8>) -> Result<usize> {
+        read_into_impl(self.id, buffer)
+    }
+
+    /// Read the payload of the key directly into a buffer which has been locked into memory.
+    ///
+    /// The payload is copied by the kernel directly into `buffer` without any intermediate
+    /// copies, so secrets stay in memory which has been locked using `mlock(2)`. Returns the size
+    /// of the payload. If it is larger than `buffer`, the contents of `buffer` are unspecified
+    /// (newer kernels leave it untouched while older kernels copy a truncated payload). Requires
+    /// `read` permissions on the key.
+    pub fn read_into_locked(&self, buffer: &mut [u8]) -> Result<usize> {
+        keyctl_read(self.id, Some(buffer.into()))
+    }
+
+    /// Read the payload of the key for display.
+    ///
+    /// If the payload consists only of printable ASCII characters, it is returned as-is.
+    /// Otherwise, it is returned as `:hex:` followed by the hex encoding of the payload. This
+    /// matches the output of `keyctl print`. Requires `read` permissions on the key.
+    pub fn print(&self) -> Result<String> {
+        let payload = self.read()?;
+        if payload.iter().all(|&c| (0x20..=0x7e).contains(&c)) {
+            // All printable ASCII is valid UTF-8.
+            Ok(String::from_utf8(payload).expect("printable ASCII should be valid UTF-8"))
+        } else {
+            Ok(format!(":hex:{:x}", keytypes::ByteBuf(&payload)))
+        }
+    }
+
+    /// Read the payload of the key as a stream of type-length-value records.
+    ///
+    /// Each record consists of a one byte type, a 32-bit length in native byte order, and then
+    /// that many bytes of value. If the payload ends in the middle of a record, `EBADMSG` is
+    /// returned. Requires `read` permissions on the key.
+    pub fn read_tlv(&self) -> Result<Vec<(u8, Vec<u8>)>> {
+        let payload = self.read()?;
+        let mut rest = &payload[..];
+        let mut records = Vec::new();
+        let len_size = mem::size_of::<u32>();
+        while let Some((&type_, tail)) = rest.split_first() {
+            if tail.len() < len_size {
+                return Err(errno::Errno(libc::EBADMSG));
+            }
+            let (len_bytes, tail) = tail.split_at(len_size);
+            let len = u32::from_ne_bytes(len_bytes.try_into().unwrap()) as usize;
+            if tail.len() < len {
+                return Err(errno::Errno(libc::EBADMSG));
+            }
+            let (value, tail) = tail.split_at(len);
+            records.push((type_, value.into()));
+            rest = tail;
+        }
+        Ok(records)
+    }
+
+    /// Read the payload of the key as a NUL-terminated C string.
+    ///
+    /// The payload must end with a single NUL byte and contain no other NUL bytes. If it does not
+    /// end with a NUL byte or contains interior NUL bytes, `EINVAL` is returned. Requires `read`
+    /// permissions on the key.
+    pub fn read_cstring(&self) -> Result<CString> {
+        let mut payload = self.read()?;
+        if payload.pop() != Some(0) {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+        CString::new(payload).map_err(|_| errno::Errno(libc::EINVAL))
+    }
+
     /// Set an expiration timer on the keyring to `timeout`.
     ///
     /// Any partial seconds are ignored. A timeout of 0 means "no expiration". Requires the
     /// `setattr` permission on the key.
+    #[deprecated(since = "0.5.0", note = "use `set_timeout_duration` instead")]
     pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
-        Keyring::new_impl(self.id).set_timeout(timeout)
+        keyctl_set_timeout(self.id, timeout.as_secs() as TimeoutSeconds)
+    }
+
+    /// Set an expiration timer on the key to `timeout`.
+    ///
+    /// `None` removes any expiration timer. Partial seconds are rounded up (so that short
+    /// timeouts do not remove the timer instead) and timeouts too large for the kernel saturate.
+    /// Requires the `setattr` permission on the key.
+    pub fn set_timeout_duration(&mut self, timeout: Option<Duration>) -> Result<()> {
+        Keyring::new_impl(self.id).set_timeout_duration(timeout)
+    }
+
+    /// The time remaining until the key expires.
+    ///
+    /// Returns `None` if the key does not expire. This information is read from `/proc/keys` which
+    /// only reports the remaining time in the largest whole unit which fits (seconds, minutes,
+    /// hours, days, or weeks), so the returned value is a lower bound. Requires `procfs` to be
+    /// mounted and `view` permission on the key. Returns `ENOKEY` if the key is not visible to the
+    /// current process.
+    pub fn timeout_remaining(&self) -> Result<Option<Duration>> {
+        Keyring::new_impl(self.id).timeout_remaining()
+    }
+
+    /// Reduce the expiration timer on the key to at most `max`.
+    ///
+    /// The timeout is never extended. It is left alone if the key is known to expire within
+    /// `max`. Since the time remaining (see `timeout_remaining`) is rounded down by the kernel, a
+    /// key which may expire after `max` is set to expire after the lower of `max` and the
+    /// reported time remaining, so it may expire up to one reported unit earlier than necessary.
+    /// Note that the timeout is read and set in separate operations, so another process may
+    /// change it in between. Requires the `setattr` permission on the key.
+    pub fn cap_timeout(&mut self, max: Duration) -> Result<()> {
+        match proc::capped_timeout(self.timeout_remaining()?, max) {
+            Some(timeout) => self.set_timeout_duration(Some(timeout)),
+            None => Ok(()),
+        }
     }
 
     /// The security context of the key.
@@ -788,6 +2336,20 @@ impl Key {
         Keyring::new_impl(self.id).security()
     }
 
+    /// Check the security context of the key against a policy.
+    ///
+    /// Returns `EKEYREJECTED` if `predicate` does not accept the security context of the key.
+    pub fn expect_security<P>(&self, predicate: P) -> Result<()>
+    where
+        P: Fn(&str) -> bool,
+    {
+        if predicate(&self.security()?) {
+            Ok(())
+        } else {
+            Err(errno::Errno(libc::EKEYREJECTED))
+        }
+    }
+
     /// Invalidates the key and schedules it for removal.
     ///
     /// Requires the `search` permission on the key.
@@ -809,33 +2371,86 @@ impl Key {
         Ok(KeyManager::new(Key::new_impl(self.id)))
     }
 
+    /// Assume authority over the key and instantiate it with the payload produced by `f`.
+    ///
+    /// This is the common structure of a `request-key` callout program. If `f` fails, the key is
+    /// negated for 60 seconds so that the requester does not wait for it and the error from `f`
+    /// is returned. The payload is cleared from memory after instantiating the key. See
+    /// `manage` for the requirements on assuming authority.
+    pub fn fulfill<'a, T, F>(&mut self, keyring: T, f: F) -> Result<()>
+    where
+        T: Into<Option<TargetKeyring<'a>>>,
+        F: FnOnce() -> Result<Vec<u8>>,
+    {
+        let manager = self.manage()?;
+        match f() {
+            Ok(mut payload) => {
+                let res = manager.instantiate(keyring, &payload);
+                zeroize(&mut payload);
+                res
+            },
+            Err(err) => {
+                if let Err(negate_err) = manager.negate(keyring, FULFILL_NEGATE_TIMEOUT) {
+                    error!(
+                        "Failed to negate the key after failing to fulfill it: {}",
+                        negate_err
+                    );
+                }
+                Err(err)
+            },
+        }
+    }
+
     /// Compute a Diffie-Hellman prime for use as a shared secret or public key.
     pub fn compute_dh(private: &Key, prime: &Key, base: &Key) -> Result<Vec<u8>> {
-        // Get the size of the description.
-        let mut sz = keyctl_dh_compute(private.id, prime.id, base.id, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_dh_compute(private.id, prime.id, base.id, Some(write_buffer))?;
+        let mut buffer = Vec::new();
+        Self::compute_dh_into(private, prime, base, &mut buffer)?;
+        Ok(buffer)
+    }
 
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
+    /// Compute a Diffie-Hellman prime into `buffer`, reusing its allocation.
+    ///
+    /// Any existing contents of `buffer` are replaced. If `buffer` already has enough capacity
+    /// (e.g., from a previous computation with the same prime), the result is computed with a
+    /// single system call. Otherwise, the size of the result is queried first. Returns the size
+    /// of the result.
+    pub fn compute_dh_into(
+        private: &Key,
+        prime: &Key,
+        base: &Key,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let capacity = buffer.capacity();
+        if capacity > 0 {
+            buffer.clear();
+            buffer.resize(capacity, 0);
+            match keyctl_dh_compute(
+                private.id,
+                prime.id,
+                base.id,
+                Some((&mut buffer[..]).into()),
+            ) {
+                Ok(sz) if sz <= buffer.len() => {
+                    buffer.truncate(sz);
+                    return Ok(sz);
+                },
+                // The kernel rejects buffers which are too small with `EOVERFLOW` rather than
+                // reporting the required size, so fall back to querying it.
+                Ok(_) | Err(errno::Errno(libc::EOVERFLOW)) => (),
+                Err(err) => return Err(err),
             }
-
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
         }
-        buffer.truncate(sz);
-        Ok(buffer)
+        read_sized(buffer, |write_buffer| {
+            keyctl_dh_compute(private.id, prime.id, base.id, write_buffer)
+        })
     }
 
     /// Compute a key from a Diffie-Hellman shared secret.
     ///
     /// The `base` key contains the remote public key to create a share secret which is then
-    /// processed using `hash`.
+    /// processed using `hash` to produce `output_len` bytes. The kernel must have the hash
+    /// algorithm available in its crypto API. Returns `EINVAL` if the name of the hash is empty
+    /// or contains a NUL byte.
     ///
     /// See [SP800-56A][] for details.
     ///
@@ -846,6 +2461,7 @@ impl Key {
         base: &Key,
         hash: KeyctlHash,
         other: Option<O>,
+        output_len: usize,
     ) -> Result<Vec<u8>>
     where
         O: AsRef<[u8]>,
@@ -856,6 +2472,7 @@ impl Key {
             base,
             hash,
             other.as_ref().map(AsRef::as_ref),
+            output_len,
         )
     }
 
@@ -865,32 +2482,24 @@ impl Key {
         base: &Key,
         hash: KeyctlHash,
         other: Option<&[u8]>,
+        output_len: usize,
     ) -> Result<Vec<u8>> {
-        // Get the size of the description.
-        let mut sz =
-            keyctl_dh_compute_kdf(private.id, prime.id, base.id, hash.hash(), other, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_dh_compute_kdf(
-                private.id,
-                prime.id,
-                base.id,
-                hash.hash(),
-                other,
-                Some(write_buffer),
-            )?;
-
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
-
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
+        let hash = hash.hash();
+        if hash.is_empty() {
+            return Err(errno::Errno(libc::EINVAL));
         }
+
+        // The KDF produces as much output as is requested.
+        let mut buffer = vec![0; output_len];
+        let write_buffer = buffer.get_backing_buffer();
+        let sz = keyctl_dh_compute_kdf(
+            private.id,
+            prime.id,
+            base.id,
+            hash,
+            other,
+            Some(write_buffer),
+        )?;
         buffer.truncate(sz);
         Ok(buffer)
     }
@@ -901,52 +2510,143 @@ impl Key {
 
     /// Query which optionally supported features may be used by the key.
     pub fn pkey_query_support(&self, query: &PublicKeyOptions) -> Result<KeySupportInfo> {
-        let info = query.info();
-        self.pkey_query_support_impl(&info)
+        self.pkey_query(&query.info())
+    }
+
+    /// Query which optionally supported features may be used by the key.
+    ///
+    /// The `info` string is passed to the kernel as-is and contains space-separated parameters
+    /// such as `enc=pkcs1 hash=sha256`. Returns `EINVAL` if it contains a NUL byte.
+    pub fn pkey_query(&self, info: &str) -> Result<KeySupportInfo> {
+        self.pkey_query_support_impl(info)
             .map(KeySupportInfo::from_c)
     }
 
+    /// Query which encoding and hash combinations are accepted by the key.
+    ///
+    /// The kernel does not report this directly, so each known combination is probed in turn.
+    /// Combinations which the kernel rejects as invalid or unavailable are omitted from the
+    /// result. Other errors are returned, as are errors from keys which do not support public key
+    /// operations at all.
+    pub fn pkey_supported_algos(&self) -> Result<AlgoSupport> {
+        // Make sure the key supports queries at all before probing.
+        self.pkey_query_support_impl("")?;
+
+        let mut support = AlgoSupport::default();
+        for encoding in KeyctlEncoding::PROBES {
+            for hash in KeyctlHash::PROBES {
+                let options = PublicKeyOptions {
+                    encoding: Some(encoding.clone()),
+                    hash: Some(hash.clone()),
+                };
+                match self.pkey_query_support_impl(&options.info()) {
+                    Ok(info) => {
+                        support
+                            .supported
+                            .push((options, KeySupportInfo::from_c(info)))
+                    },
+                    Err(errno::Errno(libc::EINVAL))
+                    | Err(errno::Errno(libc::EOPNOTSUPP))
+                    | Err(errno::Errno(libc::ENOPKG))
+                    | Err(errno::Errno(libc::ENOENT)) => (),
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(support)
+    }
+
     /// Encrypt data using the key.
     pub fn encrypt(&self, options: &PublicKeyOptions, data: &[u8]) -> Result<Vec<u8>> {
-        let info = options.info();
-        let support = self.pkey_query_support_impl(&info)?;
-        let mut buffer = Vec::with_capacity(support.max_enc_size as usize);
+        self.pkey_encrypt(&options.info(), data)
+    }
+
+    /// Encrypt data using the key.
+    ///
+    /// See `pkey_query` for the format of `info`.
+    pub fn pkey_encrypt(&self, info: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let support = self.pkey_query_support_impl(info)?;
+        let mut buffer = vec![0; support.max_enc_size as usize];
         let write_buffer = buffer.get_backing_buffer();
-        let sz = keyctl_pkey_encrypt(self.id, &info, data, write_buffer)?;
+        let sz = keyctl_pkey_encrypt(self.id, info, plaintext, write_buffer)?;
         buffer.truncate(sz);
         Ok(buffer)
     }
 
     /// Decrypt data using the key.
     pub fn decrypt(&self, options: &PublicKeyOptions, data: &[u8]) -> Result<Vec<u8>> {
-        let info = options.info();
-        let support = self.pkey_query_support_impl(&info)?;
-        let mut buffer = Vec::with_capacity(support.max_dec_size as usize);
+        self.pkey_decrypt(&options.info(), data)
+    }
+
+    /// Decrypt data using the key.
+    ///
+    /// See `pkey_query` for the format of `info`.
+    pub fn pkey_decrypt(&self, info: &str, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let support = self.pkey_query_support_impl(info)?;
+        let mut buffer = vec![0; support.max_dec_size as usize];
         let write_buffer = buffer.get_backing_buffer();
-        let sz = keyctl_pkey_decrypt(self.id, &info, data, write_buffer)?;
+        let sz = keyctl_pkey_decrypt(self.id, info, ciphertext, write_buffer)?;
         buffer.truncate(sz);
         Ok(buffer)
     }
 
     /// Sign data using the key.
     pub fn sign(&self, options: &PublicKeyOptions, data: &[u8]) -> Result<Vec<u8>> {
-        let info = options.info();
-        let support = self.pkey_query_support_impl(&info)?;
-        let mut buffer = Vec::with_capacity(support.max_sig_size as usize);
+        self.pkey_sign(&options.info(), data)
+    }
+
+    /// Sign data using the key.
+    ///
+    /// See `pkey_query` for the format of `info`.
+    pub fn pkey_sign(&self, info: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let support = self.pkey_query_support_impl(info)?;
+        let mut buffer = vec![0; support.max_sig_size as usize];
         let write_buffer = buffer.get_backing_buffer();
-        let sz = keyctl_pkey_sign(self.id, &info, data, write_buffer)?;
+        let sz = keyctl_pkey_sign(self.id, info, data, write_buffer)?;
         buffer.truncate(sz);
         Ok(buffer)
     }
 
     /// Verify a signature of the data using the key.
+    ///
+    /// See `pkey_verify` for how errors are reported.
     pub fn verify(
         &self,
         options: &PublicKeyOptions,
         data: &[u8],
         signature: &[u8],
     ) -> Result<bool> {
-        keyctl_pkey_verify(self.id, &options.info(), data, signature)
+        self.pkey_verify(&options.info(), data, signature)
+    }
+
+    /// Verify a signature of the data using the key.
+    ///
+    /// A signature which does not match is not an error: `Ok(false)` is returned when the kernel
+    /// rejects the signature (`EKEYREJECTED`). Errors are only returned when the verification
+    /// could not be performed (e.g., the key does not support verification or `info` is
+    /// invalid). See `pkey_query` for the format of `info`.
+    pub fn pkey_verify(&self, info: &str, data: &[u8], signature: &[u8]) -> Result<bool> {
+        match keyctl_pkey_verify(self.id, info, data, signature) {
+            Err(errno::Errno(libc::EKEYREJECTED)) => Ok(false),
+            res => res,
+        }
+    }
+}
+
+impl From<Keyring> for Key {
+    fn from(keyring: Keyring) -> Self {
+        Key::new_impl(keyring.id)
+    }
+}
+
+impl TryFrom<Key> for Keyring {
+    type Error = Error;
+
+    /// Convert a key into a keyring.
+    ///
+    /// Returns `ENOTDIR` if the key is not a keyring. Requires `view` permission on the key.
+    fn try_from(key: Key) -> Result<Self> {
+        key.as_keyring()?.ok_or(errno::Errno(libc::ENOTDIR))
     }
 }
 
@@ -963,42 +2663,120 @@ pub struct Description {
     pub perms: Permission,
     /// The plaintext description of the key.
     pub description: String,
+    /// Unrecognized leading fields added by newer kernels.
+    ///
+    /// These are kept in the order the kernel reported them.
+    pub extra: Vec<String>,
+}
+
+/// Errors which may occur when parsing a key description from the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DescriptionParseError {
+    /// The description did not have enough fields.
+    TooFewFields,
+    /// The owning user field was not a valid user ID.
+    BadUid,
+    /// The owning group field was not a valid group ID.
+    BadGid,
+    /// The permissions field was not a valid hexadecimal value.
+    BadPermissions,
+}
+
+impl fmt::Display for DescriptionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self {
+            DescriptionParseError::TooFewFields => "too few fields",
+            DescriptionParseError::BadUid => "invalid uid",
+            DescriptionParseError::BadGid => "invalid gid",
+            DescriptionParseError::BadPermissions => "invalid permissions",
+        };
+        f.write_str(what)
+    }
 }
 
 impl Description {
-    fn parse(desc: &str) -> Option<Description> {
+    pub(crate) fn parse(desc: &str) -> result::Result<Description, DescriptionParseError> {
         let mut pieces = desc.split(';').collect::<Vec<_>>();
         // Reverse the string because the kernel plans to extend it by adding fields to the
         // beginning of the string. By doing this, the fields are at a constant position in the
-        // split string.
+        // split string. Any additional fields are kept in `extra`.
         pieces.reverse();
-        let len = pieces.len();
-        if len < 5 {
-            None
-        } else {
-            if len > 5 {
-                error!(
-                    "New fields detected! Please report this upstream to \
-                     https://github.com/mathstuf/rust-keyutils: {}",
-                    desc,
-                );
-            }
-            let bits = KeyPermissions::from_str_radix(pieces[1], 16).unwrap();
-            if Permission::from_bits(bits).is_none() {
-                error!(
-                    "New permission bits detected! Please report this upstream to \
-                     https://github.com/mathstuf/rust-keyutils: {}",
-                    bits,
-                );
-            }
-            Some(Description {
-                type_: pieces[4].to_owned(),
-                uid: pieces[3].parse::<libc::uid_t>().unwrap(),
-                gid: pieces[2].parse::<libc::gid_t>().unwrap(),
-                perms: Permission::from_bits_truncate(bits),
-                description: pieces[0].to_owned(),
-            })
+        if pieces.len() < 5 {
+            return Err(DescriptionParseError::TooFewFields);
+        }
+        let bits = KeyPermissions::from_str_radix(pieces[1], 16)
+            .map_err(|_| DescriptionParseError::BadPermissions)?;
+        if Permission::from_bits(bits).is_none() {
+            error!(
+                "New permission bits detected! Please report this upstream to \
+                 https://github.com/mathstuf/rust-keyutils: {}",
+                bits,
+            );
+        }
+        Ok(Description {
+            type_: pieces[4].to_owned(),
+            uid: pieces[3]
+                .parse::<libc::uid_t>()
+                .map_err(|_| DescriptionParseError::BadUid)?,
+            gid: pieces[2]
+                .parse::<libc::gid_t>()
+                .map_err(|_| DescriptionParseError::BadGid)?,
+            perms: Permission::from_raw(bits),
+            description: pieces[0].to_owned(),
+            extra: pieces[5..]
+                .iter()
+                .rev()
+                .map(|&field| field.to_owned())
+                .collect(),
+        })
+    }
+
+    /// Whether `category` may view attributes of the key.
+    pub fn can_view(&self, category: Category) -> bool {
+        self.perms.contains(category.view())
+    }
+
+    /// Whether `category` may read the key.
+    pub fn can_read(&self, category: Category) -> bool {
+        self.perms.contains(category.read())
+    }
+
+    /// Whether `category` may write to the key.
+    pub fn can_write(&self, category: Category) -> bool {
+        self.perms.contains(category.write())
+    }
+
+    /// Whether `category` may search the key (or find it in a search).
+    pub fn can_search(&self, category: Category) -> bool {
+        self.perms.contains(category.search())
+    }
+
+    /// Whether `category` may link to the key.
+    pub fn can_link(&self, category: Category) -> bool {
+        self.perms.contains(category.link())
+    }
+
+    /// Whether `category` may set attributes of the key.
+    pub fn can_set_attribute(&self, category: Category) -> bool {
+        self.perms.contains(category.set_attribute())
+    }
+}
+
+impl fmt::Display for Description {
+    /// Formats the description in the format used by the kernel and `keyctl describe`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for field in &self.extra {
+            write!(f, "{};", field)?;
         }
+        write!(
+            f,
+            "{};{};{};{:08x};{}",
+            self.type_,
+            self.uid,
+            self.gid,
+            self.perms.bits(),
+            self.description,
+        )
     }
 }
 
@@ -1006,6 +2784,9 @@ impl Description {
 #[derive(Debug)]
 pub enum TargetKeyring<'a> {
     /// A special keyring.
+    ///
+    /// When responding to a key request, this refers to the keyring of the requesting process
+    /// rather than the keyring of the current process.
     Special(SpecialKeyring),
     /// A specific keyring.
     Keyring(&'a mut Keyring),
@@ -1045,6 +2826,13 @@ impl<'a> From<&'a mut Keyring> for Option<TargetKeyring<'a>> {
 }
 
 /// A manager for a key to respond to instantiate a key request by the kernel.
+///
+/// Note that the kernel has no way to instantiate a requested key as an alias of another key;
+/// the requester always receives the key under construction. To hand out an existing key
+/// instead, reject or negate the requested key and link the existing key into the keyring which
+/// the requester searches (replacing the link to the negative key if it is in the same keyring).
+/// The request itself still fails immediately with `ENOKEY` (or the rejection error) and the
+/// kernel does not search again, so the requester must retry a search itself to find the key.
 #[derive(Debug, PartialEq, Eq)]
 pub struct KeyManager {
     key: Key,
@@ -1089,6 +2877,37 @@ impl KeyManager {
         )
     }
 
+    /// Instantiate the key with a payload gathered from multiple buffers.
+    ///
+    /// The kernel concatenates `payloads` into the payload of the key, so they do not need to be
+    /// copied into a single buffer first. Returns `EINVAL` if more than `UIO_MAXIOV` (1024)
+    /// buffers are given.
+    pub fn instantiate_iov<'a, T>(self, keyring: T, payloads: &[&[u8]]) -> Result<()>
+    where
+        T: Into<Option<TargetKeyring<'a>>>,
+    {
+        if payloads.len() > libc::UIO_MAXIOV as usize {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+        keyctl_instantiate_iov(
+            self.key.id,
+            payloads,
+            keyring.into().map(TargetKeyring::serial),
+        )
+    }
+
+    /// Instantiate the key with the payload of `source`.
+    ///
+    /// The payload is cleared from memory after instantiating the key. Requires `read`
+    /// permission on `source`.
+    pub fn instantiate_from<'a, T>(self, source: &Key, keyring: T) -> Result<()>
+    where
+        T: Into<Option<TargetKeyring<'a>>>,
+    {
+        let mut payload = source.read()?;
+        with_zeroized(&mut payload, |payload| self.instantiate(keyring, payload))
+    }
+
     /// Reject the key with the given `error`.
     ///
     /// Requests for the key will fail until `timeout` has elapsed (partial
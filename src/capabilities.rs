@@ -0,0 +1,141 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use keyutils_raw::*;
+
+use crate::Result;
+
+/// The keyutils features supported by the running kernel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    caps: [u8; 2],
+}
+
+impl Capabilities {
+    fn has(&self, index: usize, flag: u8) -> bool {
+        self.caps[index] & flag != 0
+    }
+
+    /// Whether the kernel supports persistent keyrings.
+    pub fn supports_persistent_keyrings(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_PERSISTENT_KEYRINGS)
+    }
+
+    /// Whether the kernel supports Diffie-Hellman computations.
+    pub fn supports_dh_compute(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_DIFFIE_HELLMAN)
+    }
+
+    /// Whether the kernel supports public key operations.
+    pub fn supports_pkey(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_PUBLIC_KEY)
+    }
+
+    /// Whether the kernel supports big keys.
+    pub fn supports_big_key(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_BIG_KEY)
+    }
+
+    /// Whether the kernel supports invalidating keys.
+    pub fn supports_invalidate(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_INVALIDATE)
+    }
+
+    /// Whether the kernel supports restricting links into keyrings.
+    pub fn supports_restrict_keyring(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_RESTRICT_KEYRING)
+    }
+
+    /// Whether the kernel supports moving keys between keyrings.
+    pub fn supports_move(&self) -> bool {
+        self.has(0, KEYCTL_CAPS0_MOVE)
+    }
+
+    /// Whether keyring names are separated by user namespace.
+    pub fn supports_namespaced_keyring_names(&self) -> bool {
+        self.has(1, KEYCTL_CAPS1_NS_KEYRING_NAME)
+    }
+
+    /// Whether keys are tagged by network namespace.
+    pub fn supports_namespaced_key_tags(&self) -> bool {
+        self.has(1, KEYCTL_CAPS1_NS_KEY_TAG)
+    }
+
+    /// Whether the kernel supports key change notifications.
+    pub fn supports_notifications(&self) -> bool {
+        self.has(1, KEYCTL_CAPS1_NOTIFICATIONS)
+    }
+}
+
+/// Query the keyutils features supported by the running kernel.
+///
+/// Kernels which predate the query (Linux 5.3) are reported as supporting nothing.
+pub fn capabilities() -> Result<Capabilities> {
+    let mut caps = Capabilities::default();
+    match keyctl_capabilities(Some((&mut caps.caps[..]).into())) {
+        Ok(_) => Ok(caps),
+        Err(errno::Errno(libc::EOPNOTSUPP)) => Ok(Capabilities::default()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_flags() {
+        let caps = Capabilities {
+            caps: [
+                KEYCTL_CAPS0_CAPABILITIES | KEYCTL_CAPS0_MOVE | KEYCTL_CAPS0_PUBLIC_KEY,
+                KEYCTL_CAPS1_NOTIFICATIONS,
+            ],
+        };
+
+        assert!(caps.supports_move());
+        assert!(caps.supports_pkey());
+        assert!(caps.supports_notifications());
+        assert!(!caps.supports_dh_compute());
+        assert!(!caps.supports_restrict_keyring());
+        assert!(!caps.supports_namespaced_key_tags());
+    }
+
+    #[test]
+    fn test_capabilities_default() {
+        let caps = Capabilities::default();
+
+        assert!(!caps.supports_persistent_keyrings());
+        assert!(!caps.supports_dh_compute());
+        assert!(!caps.supports_pkey());
+        assert!(!caps.supports_big_key());
+        assert!(!caps.supports_invalidate());
+        assert!(!caps.supports_restrict_keyring());
+        assert!(!caps.supports_move());
+        assert!(!caps.supports_namespaced_keyring_names());
+        assert!(!caps.supports_namespaced_key_tags());
+        assert!(!caps.supports_notifications());
+    }
+}
@@ -27,6 +27,8 @@
 use bitflags::bitflags;
 use keyutils_raw::*;
 
+use crate::Result;
+
 /// Special keyrings predefined for a process.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 // #[non_exhaustive]
@@ -72,6 +74,10 @@ bitflags! {
     /// "possession" requires the `search` permission, association from the calling thread
     /// (the session, process, and thread keyrings), or is linked to from a possessed keyring. See
     /// `keyrings(7)` for complete details.
+    ///
+    /// The flags correspond to the `KEY_POS_*`, `KEY_USR_*`, `KEY_GRP_*`, and `KEY_OTH_*`
+    /// constants from `keyutils.h` (e.g., `POSSESSOR_SET_ATTRIBUTE` is `KEY_POS_SETATTR`). Use
+    /// `from_raw` and `to_raw` to convert to and from the raw `KeyPermissions` mask.
     pub struct Permission: KeyPermissions {
         /// Possession allows viewing attributes about the key or keyring.
         const POSSESSOR_VIEW            = KEY_POS_VIEW;
@@ -146,6 +152,283 @@ bitflags! {
     }
 }
 
+impl Permission {
+    /// Create a permission set from a raw mask.
+    ///
+    /// Unlike `from_bits_truncate`, bits which do not correspond to a known permission are
+    /// preserved so that permissions added by newer kernels round-trip.
+    pub fn from_raw(raw: KeyPermissions) -> Self {
+        Permission {
+            bits: raw,
+        }
+    }
+
+    /// The raw mask of the permission set, including any unknown bits.
+    pub fn to_raw(self) -> KeyPermissions {
+        self.bits
+    }
+
+    /// Parse a permission specification.
+    ///
+    /// The specification is a `;`-separated list of `actor=flag,flag` entries. Actors are
+    /// `possessor`, `user`, `group`, and `other`. Flags are `view`, `read`, `write`, `search`,
+    /// `link`, `setattr`, and `all`. For example, `possessor=all;user=view,read`. Returns `EINVAL`
+    /// for malformed specifications.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || errno::Errno(libc::EINVAL);
+        let mut perms = Permission::empty();
+
+        for entry in spec
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let mut parts = entry.splitn(2, '=');
+            let actor = parts.next().map(str::trim).ok_or_else(invalid)?;
+            let flags = parts.next().ok_or_else(invalid)?;
+            let shift = PERMISSION_ACTORS
+                .iter()
+                .find(|(name, _)| *name == actor)
+                .map(|&(_, shift)| shift)
+                .ok_or_else(invalid)?;
+
+            for flag in flags.split(',').map(str::trim) {
+                let bits = if flag == "all" {
+                    KEY_OTH_ALL
+                } else {
+                    PERMISSION_FLAGS
+                        .iter()
+                        .find(|(name, _)| *name == flag)
+                        .map(|&(_, bits)| bits)
+                        .ok_or_else(invalid)?
+                };
+                perms |= Permission::from_raw(bits << shift);
+            }
+        }
+
+        Ok(perms)
+    }
+
+    /// Format the permission set as a specification accepted by `parse`.
+    ///
+    /// Actors are listed in the order `possessor`, `user`, `group`, `other` and actors without
+    /// any permissions are omitted. Unknown bits are not represented.
+    pub fn to_spec_string(self) -> String {
+        PERMISSION_ACTORS
+            .iter()
+            .filter_map(|&(actor, shift)| {
+                let bits = (self.bits >> shift) & KEY_OTH_ALL;
+                if bits == 0 {
+                    None
+                } else if bits == KEY_OTH_ALL {
+                    Some(format!("{}=all", actor))
+                } else {
+                    let flags = PERMISSION_FLAGS
+                        .iter()
+                        .filter(|&&(_, flag)| bits & flag != 0)
+                        .map(|&(name, _)| name)
+                        .collect::<Vec<_>>();
+                    Some(format!("{}={}", actor, flags.join(",")))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+/// The classes of accessors which are granted permissions on a key.
+///
+/// See `Permission` for how the kernel selects which categories apply to an accessor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Accessors which possess the key.
+    Possessor,
+    /// Accessors whose user ID matches the owner of the key.
+    User,
+    /// Accessors whose group IDs include the group of the key.
+    Group,
+    /// All other accessors.
+    Other,
+}
+
+macro_rules! category_permission_methods {
+    ($( $(#[$attr:meta])* $name:ident => $bits:ident; )*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(self) -> Permission {
+                Permission::from_raw($bits << self.shift())
+            }
+        )*
+    };
+}
+
+impl Category {
+    fn shift(self) -> u32 {
+        match self {
+            Category::Possessor => 24,
+            Category::User => 16,
+            Category::Group => 8,
+            Category::Other => 0,
+        }
+    }
+
+    category_permission_methods! {
+        /// The permission to view attributes for the category.
+        view => KEY_OTH_VIEW;
+        /// The permission to read for the category.
+        read => KEY_OTH_READ;
+        /// The permission to write for the category.
+        write => KEY_OTH_WRITE;
+        /// The permission to search for the category.
+        search => KEY_OTH_SEARCH;
+        /// The permission to link for the category.
+        link => KEY_OTH_LINK;
+        /// The permission to set attributes for the category.
+        set_attribute => KEY_OTH_SETATTR;
+        /// All permissions for the category.
+        all => KEY_OTH_ALL;
+    }
+}
+
+/// A builder for permission sets.
+///
+/// This allows composing permissions without combining the `Permission` flags by hand.
+///
+/// ```
+/// # use keyutils::{Permission, PermissionBuilder};
+/// let perms = PermissionBuilder::new()
+///     .possessor_all()
+///     .user_view()
+///     .user_read()
+///     .build();
+/// assert_eq!(
+///     perms,
+///     Permission::POSSESSOR_ALL | Permission::USER_VIEW | Permission::USER_READ,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermissionBuilder {
+    perms: Permission,
+}
+
+macro_rules! permission_builder_methods {
+    ($( $(#[$attr:meta])* $name:ident => $flag:ident; )*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(mut self) -> Self {
+                self.perms |= Permission::$flag;
+                self
+            }
+        )*
+    };
+}
+
+impl PermissionBuilder {
+    /// Create a builder which grants no permissions.
+    pub fn new() -> Self {
+        Self::from_raw(0)
+    }
+
+    /// Create a builder starting from a raw permission mask.
+    pub fn from_raw(raw: KeyPermissions) -> Self {
+        PermissionBuilder {
+            perms: Permission::from_raw(raw),
+        }
+    }
+
+    permission_builder_methods! {
+        /// Allow viewing attributes by the possessor.
+        possessor_view => POSSESSOR_VIEW;
+        /// Allow reading by the possessor.
+        possessor_read => POSSESSOR_READ;
+        /// Allow writing by the possessor.
+        possessor_write => POSSESSOR_WRITE;
+        /// Allow searching by the possessor.
+        possessor_search => POSSESSOR_SEARCH;
+        /// Allow linking by the possessor.
+        possessor_link => POSSESSOR_LINK;
+        /// Allow setting attributes by the possessor.
+        possessor_setattr => POSSESSOR_SET_ATTRIBUTE;
+        /// Grant all permissions to the possessor.
+        possessor_all => POSSESSOR_ALL;
+        /// Allow viewing attributes by the owning user.
+        user_view => USER_VIEW;
+        /// Allow reading by the owning user.
+        user_read => USER_READ;
+        /// Allow writing by the owning user.
+        user_write => USER_WRITE;
+        /// Allow searching by the owning user.
+        user_search => USER_SEARCH;
+        /// Allow linking by the owning user.
+        user_link => USER_LINK;
+        /// Allow setting attributes by the owning user.
+        user_setattr => USER_SET_ATTRIBUTE;
+        /// Grant all permissions to the owning user.
+        user_all => USER_ALL;
+        /// Allow viewing attributes by the owning group.
+        group_view => GROUP_VIEW;
+        /// Allow reading by the owning group.
+        group_read => GROUP_READ;
+        /// Allow writing by the owning group.
+        group_write => GROUP_WRITE;
+        /// Allow searching by the owning group.
+        group_search => GROUP_SEARCH;
+        /// Allow linking by the owning group.
+        group_link => GROUP_LINK;
+        /// Allow setting attributes by the owning group.
+        group_setattr => GROUP_SET_ATTRIBUTE;
+        /// Grant all permissions to the owning group.
+        group_all => GROUP_ALL;
+        /// Allow viewing attributes by everyone else.
+        other_view => OTHER_VIEW;
+        /// Allow reading by everyone else.
+        other_read => OTHER_READ;
+        /// Allow writing by everyone else.
+        other_write => OTHER_WRITE;
+        /// Allow searching by everyone else.
+        other_search => OTHER_SEARCH;
+        /// Allow linking by everyone else.
+        other_link => OTHER_LINK;
+        /// Allow setting attributes by everyone else.
+        other_setattr => OTHER_SET_ATTRIBUTE;
+        /// Grant all permissions to everyone else.
+        other_all => OTHER_ALL;
+    }
+
+    /// The permission set.
+    pub fn build(self) -> Permission {
+        self.perms
+    }
+}
+
+impl Default for PermissionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Permission> for PermissionBuilder {
+    fn from(perms: Permission) -> Self {
+        PermissionBuilder {
+            perms,
+        }
+    }
+}
+
+/// Actor names in permission specifications and the shift of their permission bits.
+const PERMISSION_ACTORS: &[(&str, u32)] =
+    &[("possessor", 24), ("user", 16), ("group", 8), ("other", 0)];
+
+/// Flag names in permission specifications and their (unshifted) permission bits.
+const PERMISSION_FLAGS: &[(&str, KeyPermissions)] = &[
+    ("view", KEY_OTH_VIEW),
+    ("read", KEY_OTH_READ),
+    ("write", KEY_OTH_WRITE),
+    ("search", KEY_OTH_SEARCH),
+    ("link", KEY_OTH_LINK),
+    ("setattr", KEY_OTH_SETATTR),
+];
+
 /// They kernel type for representing support for optional features.
 ///
 /// Asymmetric keys might only support a limited set of operations. These flags indicate which
@@ -234,3 +517,81 @@ fn test_support_flags() {
         KEYCTL_SUPPORTS_VERIFY,
     );
 }
+
+#[test]
+fn test_permission_raw_round_trip() {
+    let unknown = 0x4000_0000;
+    let raw = unknown | KEY_POS_ALL | KEY_USR_VIEW;
+    let perms = Permission::from_raw(raw);
+
+    assert!(perms.contains(Permission::POSSESSOR_ALL | Permission::USER_VIEW));
+    assert_eq!(perms.to_raw(), raw);
+    assert_eq!(Permission::from_raw(perms.to_raw()), perms);
+    assert_eq!(
+        Permission::from_bits_truncate(raw),
+        Permission::POSSESSOR_ALL | Permission::USER_VIEW,
+    );
+}
+
+#[test]
+fn test_permission_parse() {
+    let perms = Permission::parse("possessor=view,read;user=all;other=search").unwrap();
+    assert_eq!(
+        perms,
+        Permission::POSSESSOR_VIEW
+            | Permission::POSSESSOR_READ
+            | Permission::USER_ALL
+            | Permission::OTHER_SEARCH,
+    );
+    assert_eq!(Permission::parse("").unwrap(), Permission::empty());
+}
+
+#[test]
+fn test_permission_parse_invalid() {
+    let invalid = errno::Errno(libc::EINVAL);
+    assert_eq!(Permission::parse("possessor").unwrap_err(), invalid);
+    assert_eq!(Permission::parse("owner=view").unwrap_err(), invalid);
+    assert_eq!(Permission::parse("user=view,execute").unwrap_err(), invalid);
+    assert_eq!(Permission::parse("user=").unwrap_err(), invalid);
+}
+
+#[test]
+fn test_permission_spec_round_trip() {
+    let spec = "possessor=all;user=view,read,search;group=view";
+    let perms = Permission::parse(spec).unwrap();
+    assert_eq!(perms.to_spec_string(), spec);
+    assert_eq!(Permission::parse(&perms.to_spec_string()).unwrap(), perms);
+
+    let unordered =
+        Permission::parse(" group = view ; user=search,view,read;possessor=all").unwrap();
+    assert_eq!(unordered.to_spec_string(), spec);
+}
+
+#[test]
+fn test_permission_builder() {
+    let perms = PermissionBuilder::new()
+        .possessor_all()
+        .user_view()
+        .group_search()
+        .other_setattr()
+        .build();
+    assert_eq!(
+        perms,
+        Permission::POSSESSOR_ALL
+            | Permission::USER_VIEW
+            | Permission::GROUP_SEARCH
+            | Permission::OTHER_SET_ATTRIBUTE,
+    );
+    assert_eq!(PermissionBuilder::default().build(), Permission::empty());
+}
+
+#[test]
+fn test_permission_builder_from_raw() {
+    let raw = Permission::POSSESSOR_VIEW.to_raw() | 0x8000_0000;
+    let perms = PermissionBuilder::from_raw(raw).user_read().build();
+    assert_eq!(perms.to_raw(), raw | KEY_USR_READ);
+    assert_eq!(
+        PermissionBuilder::from(Permission::USER_ALL).build(),
+        Permission::USER_ALL,
+    );
+}
@@ -0,0 +1,166 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// An error with descriptions specific to the key management facility.
+///
+/// The kernel reuses `errno` values for key-specific conditions (e.g., `EKEYREVOKED` for a
+/// revoked key). This wraps the raw `Errno` and describes those conditions in terms of keys.
+/// Any `Result` from this crate may be converted using `map_err(KeyError::from)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyError(pub errno::Errno);
+
+impl KeyError {
+    /// The raw error.
+    pub fn errno(self) -> errno::Errno {
+        self.0
+    }
+
+    /// Whether the key has expired (`EKEYEXPIRED`).
+    pub fn is_expired(self) -> bool {
+        self.0 .0 == libc::EKEYEXPIRED
+    }
+
+    /// Whether the key has been revoked (`EKEYREVOKED`).
+    pub fn is_revoked(self) -> bool {
+        self.0 .0 == libc::EKEYREVOKED
+    }
+
+    /// Whether the key has been rejected (`EKEYREJECTED`).
+    pub fn is_rejected(self) -> bool {
+        self.0 .0 == libc::EKEYREJECTED
+    }
+
+    /// Whether the key was not found or has been removed (`ENOKEY`).
+    pub fn is_not_found(self) -> bool {
+        self.0 .0 == libc::ENOKEY
+    }
+
+    /// Whether the key quota of the user has been exceeded (`EDQUOT`).
+    pub fn is_quota_exceeded(self) -> bool {
+        self.0 .0 == libc::EDQUOT
+    }
+
+    fn key_description(self) -> Option<&'static str> {
+        Some(match self.0 .0 {
+            libc::EKEYEXPIRED => "key has expired",
+            libc::EKEYREVOKED => "key was revoked",
+            libc::EKEYREJECTED => "key was rejected",
+            libc::ENOKEY => "key not found",
+            libc::EDQUOT => "key quota exceeded",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.key_description() {
+            Some(desc) => write!(f, "{} (os error {})", desc, self.0 .0),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl Error for KeyError {}
+
+impl From<errno::Errno> for KeyError {
+    fn from(errno: errno::Errno) -> Self {
+        KeyError(errno)
+    }
+}
+
+impl From<KeyError> for errno::Errno {
+    fn from(err: KeyError) -> Self {
+        err.0
+    }
+}
+
+impl From<KeyError> for io::Error {
+    fn from(err: KeyError) -> Self {
+        err.0.into()
+    }
+}
+
+impl PartialEq<errno::Errno> for KeyError {
+    fn eq(&self, other: &errno::Errno) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::KeyError;
+
+    #[test]
+    fn test_key_error_display() {
+        let err = KeyError(errno::Errno(libc::EKEYREVOKED));
+        assert_eq!(
+            err.to_string(),
+            format!("key was revoked (os error {})", libc::EKEYREVOKED),
+        );
+
+        let err = KeyError(errno::Errno(libc::EINVAL));
+        assert_eq!(err.to_string(), errno::Errno(libc::EINVAL).to_string());
+    }
+
+    #[test]
+    fn test_key_error_conversions() {
+        let errno = errno::Errno(libc::ENOKEY);
+        let err = KeyError::from(errno);
+        assert_eq!(err, errno);
+        assert_eq!(err.errno(), errno);
+        assert_eq!(errno::Errno::from(err), errno);
+        assert_eq!(io::Error::from(err).raw_os_error(), Some(libc::ENOKEY));
+    }
+
+    #[test]
+    fn test_key_error_predicates() {
+        let check = |code, expected: [bool; 5]| {
+            let err = KeyError(errno::Errno(code));
+            let actual = [
+                err.is_expired(),
+                err.is_revoked(),
+                err.is_rejected(),
+                err.is_not_found(),
+                err.is_quota_exceeded(),
+            ];
+            assert_eq!(actual, expected);
+        };
+
+        check(libc::EKEYEXPIRED, [true, false, false, false, false]);
+        check(libc::EKEYREVOKED, [false, true, false, false, false]);
+        check(libc::EKEYREJECTED, [false, false, true, false, false]);
+        check(libc::ENOKEY, [false, false, false, true, false]);
+        check(libc::EDQUOT, [false, false, false, false, true]);
+        check(libc::EINVAL, [false, false, false, false, false]);
+    }
+}
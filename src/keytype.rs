@@ -26,6 +26,8 @@
 
 use std::borrow::Cow;
 
+use crate::Result;
+
 /// A trait for representing a type of key in the Linux keyring subsystem.
 pub trait KeyType {
     /// The type for describing the key.
@@ -91,6 +93,24 @@ impl KeyPayload for Vec<u8> {
     }
 }
 
+/// A key type for which the kernel may generate the key material.
+pub trait GeneratableKeyType: KeyType {
+    /// Options for generating a key of this type.
+    type Options;
+
+    /// The payload which instructs the kernel to generate a key of `len` bytes.
+    fn generate_payload(len: usize, options: &Self::Options) -> Box<Self::Payload>;
+}
+
+/// A key type whose payload may be read back and interpreted.
+pub trait ReadableKeyType: KeyType {
+    /// The interpreted payload of a key of this type.
+    type Output;
+
+    /// Interpret the payload of a key of this type as read from the kernel.
+    fn parse_payload(payload: &[u8]) -> Result<Self::Output>;
+}
+
 /// A key which may be restricted into being added to a keyring.
 pub trait RestrictableKeyType: KeyType {
     /// The type for representing a restriction for adding keys of this type.
@@ -29,7 +29,7 @@
 use std::borrow::Cow;
 
 use crate::keytype::*;
-use crate::{Key, Keyring, KeyringSerial};
+use crate::{Key, Keyring, KeyringSerial, Restriction};
 
 /// Asymmetric keys support encrypting, decrypting, signing, and verifying data.
 ///
@@ -117,3 +117,117 @@ impl KeyRestriction for AsymmetricRestriction {
 impl RestrictableKeyType for Asymmetric {
     type Restriction = AsymmetricRestriction;
 }
+
+/// A builder for restrictions which only accept keys signed by trusted keys.
+///
+/// This is the restriction used for setting up keyrings which only accept certificates which
+/// chain up to a given certificate authority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestrictionBuilder {
+    restriction: AsymmetricRestriction,
+}
+
+impl RestrictionBuilder {
+    /// Only allow keys which have been signed by the given key.
+    pub fn signed_by_key(key: &Key) -> Self {
+        RestrictionBuilder {
+            restriction: AsymmetricRestriction::Key {
+                key: key.clone(),
+                chained: false,
+            },
+        }
+    }
+
+    /// Only allow keys which have been signed by a key on the given keyring.
+    pub fn signed_by_keyring(keyring: &Keyring) -> Self {
+        RestrictionBuilder {
+            restriction: AsymmetricRestriction::Keyring {
+                keyring: keyring.clone(),
+                chained: false,
+            },
+        }
+    }
+
+    /// Only allow keys which have been signed by a key on the builtin trusted keyring.
+    pub fn builtin_trusted() -> Self {
+        RestrictionBuilder {
+            restriction: AsymmetricRestriction::BuiltinTrusted,
+        }
+    }
+
+    /// Only allow keys which have been signed by a key on the builtin or secondary trusted
+    /// keyrings.
+    pub fn builtin_and_secondary_trusted() -> Self {
+        RestrictionBuilder {
+            restriction: AsymmetricRestriction::BuiltinAndSecondaryTrusted,
+        }
+    }
+
+    /// Also accept keys signed by keys already in the restricted keyring.
+    ///
+    /// This only has an effect on restrictions based on a key or keyring.
+    pub fn chain(mut self) -> Self {
+        match self.restriction {
+            AsymmetricRestriction::Key {
+                ref mut chained, ..
+            }
+            | AsymmetricRestriction::Keyring {
+                ref mut chained, ..
+            } => *chained = true,
+            _ => (),
+        }
+        self
+    }
+
+    /// Build the restriction.
+    pub fn build(&self) -> Restriction {
+        Restriction::by_type::<Asymmetric, _>(&self.restriction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::RestrictionBuilder;
+    use crate::{Key, Keyring, KeyringSerial, Restriction};
+
+    fn check(builder: RestrictionBuilder, expected: &str) {
+        assert_eq!(
+            builder.build(),
+            Restriction::ByType {
+                type_: Cow::Borrowed("asymmetric"),
+                restriction: expected.into(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_restriction_builder() {
+        let serial = KeyringSerial::new(1234).unwrap();
+        let key = unsafe { Key::new(serial) };
+        let keyring = unsafe { Keyring::new(serial) };
+
+        check(RestrictionBuilder::builtin_trusted(), "builtin_trusted");
+        check(
+            RestrictionBuilder::builtin_and_secondary_trusted(),
+            "builtin_and_secondary_trusted",
+        );
+        check(
+            RestrictionBuilder::signed_by_key(&key),
+            "key_or_keyring:1234",
+        );
+        check(
+            RestrictionBuilder::signed_by_key(&key).chain(),
+            "key_or_keyring:1234:chain",
+        );
+        check(
+            RestrictionBuilder::signed_by_keyring(&keyring),
+            "key_or_keyring:1234",
+        );
+        check(
+            RestrictionBuilder::signed_by_keyring(&keyring).chain(),
+            "key_or_keyring:1234:chain",
+        );
+    }
+}
@@ -32,7 +32,9 @@ use keyutils_raw::KEY_TYPE_BIG_KEY;
 /// Big keys.
 ///
 /// These keys support being stored in a shm file if the size is too large. The file is
-/// encrypted with an ephemeral key.
+/// encrypted with an ephemeral key. This is transparent to userspace: reading the key returns
+/// the original payload. Payloads must be smaller than 1 MiB; larger payloads are rejected with
+/// `EINVAL`.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct BigKey;
 
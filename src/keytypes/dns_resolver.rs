@@ -25,10 +25,18 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 //! DNS resolution keys
+//!
+//! These keys are used by in-kernel network filesystems (e.g., CIFS and AFS) to resolve names.
+//! They may not be added from userspace; instead, they are requested and the kernel constructs
+//! them via `/sbin/request-key`, which must be configured with a `dns_resolver` callout (usually
+//! `key.dns_resolver` from `keyutils`). Without it, requests fail with `ENOKEY`.
 
 use std::borrow::Cow;
+use std::net::IpAddr;
+use std::str;
 
 use crate::keytype::*;
+use crate::{Key, Result};
 
 /// A DNS resolver key.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -43,6 +51,15 @@ impl KeyType for DnsResolver {
     }
 }
 
+impl ReadableKeyType for DnsResolver {
+    /// The resolved addresses.
+    type Output = Vec<IpAddr>;
+
+    fn parse_payload(payload: &[u8]) -> Result<Self::Output> {
+        parse_addresses(payload)
+    }
+}
+
 /// The DNS record to lookup.
 #[derive(Debug, Clone, Eq)]
 // #[non_exhaustive]
@@ -94,3 +111,59 @@ impl KeyDescription for Description {
         }
     }
 }
+
+/// Parse the payload of a DNS resolver key into its addresses.
+///
+/// The payload of an `A` or `AAAA` lookup is a comma-separated list of addresses. Returns
+/// `EINVAL` if the payload contains anything which is not an IP address (e.g., the binary
+/// payload of an AFS lookup).
+pub fn parse_addresses(payload: &[u8]) -> Result<Vec<IpAddr>> {
+    let payload = str::from_utf8(payload).map_err(|_| errno::Errno(libc::EINVAL))?;
+    let payload = payload.trim_end_matches('\0').trim();
+    if payload.is_empty() {
+        return Ok(Vec::new());
+    }
+    payload
+        .split(',')
+        .map(|addr| addr.trim().parse().map_err(|_| errno::Errno(libc::EINVAL)))
+        .collect()
+}
+
+/// Read the resolved addresses of a DNS resolver key.
+///
+/// See `parse_addresses`. Requires `read` permission on the key.
+pub fn read_addresses(key: &Key) -> Result<Vec<IpAddr>> {
+    parse_addresses(&key.read()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use super::parse_addresses;
+
+    #[test]
+    fn test_dns_parse_addresses() {
+        assert!(parse_addresses(b"").unwrap().is_empty());
+        assert_eq!(
+            parse_addresses(b"192.0.2.1").unwrap(),
+            [IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))],
+        );
+        assert_eq!(
+            parse_addresses(b"192.0.2.1,2001:db8::1\0").unwrap(),
+            [
+                IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)),
+                IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_dns_parse_addresses_invalid() {
+        let invalid = errno::Errno(libc::EINVAL);
+        assert_eq!(parse_addresses(b"example.com").unwrap_err(), invalid);
+        assert_eq!(parse_addresses(b"192.0.2.1,").unwrap_err(), invalid);
+        assert_eq!(parse_addresses(b"\0\x01\x02").unwrap_err(), invalid);
+        assert_eq!(parse_addresses(b"\xff").unwrap_err(), invalid);
+    }
+}
@@ -28,7 +28,6 @@
 
 use std::borrow::Cow;
 
-use super::ByteBuf;
 use crate::keytype::*;
 
 /// Encrypted keys.
@@ -133,6 +132,9 @@ pub enum Payload {
     /// Use this with `add_key`.
     Load {
         /// The blob to load into the key.
+        ///
+        /// This is the payload as read from an existing encrypted key (the format, master key,
+        /// key length, and the hex-encoded encrypted data). It is passed to the kernel as-is.
         blob: Vec<u8>,
     },
     /// Update a key.
@@ -165,16 +167,77 @@ impl KeyPayload for Payload {
                     description,
                     keylen,
                 )
+                .into_bytes()
             },
             Payload::Load {
                 blob,
-            } => format!("load {:x}", ByteBuf(&blob)),
+            } => [&b"load "[..], blob].concat(),
             Payload::Update {
                 keytype,
                 description,
-            } => format!("update {}:{}", keytype.name(), description),
+            } => format!("update {}:{}", keytype.name(), description).into_bytes(),
         }
-        .into_bytes()
         .into()
     }
 }
+
+/// Options for generating a new encrypted key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerateOptions {
+    /// The format of the new key.
+    pub format: Option<Format>,
+    /// The type of key to use for encrypting the new key.
+    pub keytype: MasterKeyType,
+    /// The description of the key to use for encrypting the new key.
+    pub description: Cow<'static, str>,
+}
+
+impl GeneratableKeyType for Encrypted {
+    type Options = GenerateOptions;
+
+    fn generate_payload(len: usize, options: &Self::Options) -> Box<Self::Payload> {
+        Box::new(Payload::New {
+            format: options.format,
+            keytype: options.keytype,
+            description: options.description.clone(),
+            keylen: len,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::KeyPayload;
+
+    use super::{Format, MasterKeyType, Payload};
+
+    fn check(payload: Payload, expected: &[u8]) {
+        assert_eq!(payload.payload(), expected);
+    }
+
+    #[test]
+    fn test_payload_commands() {
+        check(
+            Payload::New {
+                format: Some(Format::Enc32),
+                keytype: MasterKeyType::Trusted,
+                description: "master".into(),
+                keylen: 32,
+            },
+            b"new enc32 trusted:master 32",
+        );
+        check(
+            Payload::Load {
+                blob: b"default user:master 32 abcdef".to_vec(),
+            },
+            b"load default user:master 32 abcdef",
+        );
+        check(
+            Payload::Update {
+                keytype: MasterKeyType::User,
+                description: "master".into(),
+            },
+            b"update user:master",
+        );
+    }
+}
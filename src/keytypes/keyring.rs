@@ -27,7 +27,8 @@
 //! Keyrings
 
 use crate::keytype::*;
-use keyutils_raw::KEY_TYPE_KEYRING;
+use crate::Result;
+use keyutils_raw::{KeyringSerial, KEY_TYPE_KEYRING};
 
 /// Keyrings contain other keys.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -43,3 +44,12 @@ impl KeyType for Keyring {
         KEY_TYPE_KEYRING
     }
 }
+
+impl ReadableKeyType for Keyring {
+    /// The serials of the children of the keyring.
+    type Output = Vec<KeyringSerial>;
+
+    fn parse_payload(payload: &[u8]) -> Result<Self::Output> {
+        crate::api::parse_serials(payload)
+    }
+}
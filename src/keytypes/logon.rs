@@ -33,8 +33,12 @@ use std::borrow::Cow;
 use keyutils_raw::KEY_TYPE_LOGON;
 
 use crate::keytype::*;
+use crate::Result;
 
 /// Keys which can only be created and updated from userspace but not read back.
+///
+/// Reading the payload of a logon key always fails with `EOPNOTSUPP`. The description must start
+/// with a non-empty service prefix followed by a colon (e.g., `cifs:password`).
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Logon;
 
@@ -52,10 +56,48 @@ impl KeyType for Logon {
 /// The description of a logon key.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Description {
-    /// They subtype of the key.
-    pub subtype: Cow<'static, str>,
-    /// The description of the key.
-    pub description: Cow<'static, str>,
+    subtype: Cow<'static, str>,
+    description: Cow<'static, str>,
+}
+
+impl Description {
+    /// Create a logon key description.
+    ///
+    /// Returns `EINVAL` if `subtype` is empty since the kernel requires a service prefix.
+    pub fn new<S, D>(subtype: S, description: D) -> Result<Self>
+    where
+        S: Into<Cow<'static, str>>,
+        D: Into<Cow<'static, str>>,
+    {
+        let subtype = subtype.into();
+        if subtype.is_empty() {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+        Ok(Description {
+            subtype,
+            description: description.into(),
+        })
+    }
+
+    /// Parse a logon key description in `subtype:description` format.
+    ///
+    /// Returns `EINVAL` if there is no colon or the service prefix before it is empty.
+    pub fn parse(desc: &str) -> Result<Self> {
+        let mut parts = desc.splitn(2, ':');
+        let subtype = parts.next().unwrap_or_default();
+        let description = parts.next().ok_or(errno::Errno(libc::EINVAL))?;
+        Self::new(subtype.to_string(), description.to_string())
+    }
+
+    /// The subtype (service prefix) of the key.
+    pub fn subtype(&self) -> &str {
+        &self.subtype
+    }
+
+    /// The description of the key after the service prefix.
+    pub fn name(&self) -> &str {
+        &self.description
+    }
 }
 
 impl KeyDescription for Description {
@@ -63,3 +105,34 @@ impl KeyDescription for Description {
         format!("{}:{}", self.subtype, self.description).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::Description;
+    use crate::keytype::KeyDescription;
+
+    #[test]
+    fn test_logon_description() {
+        let desc = Description::new("service", "name").unwrap();
+        assert_eq!(desc.description(), Cow::Borrowed("service:name"));
+    }
+
+    #[test]
+    fn test_logon_description_empty_subtype() {
+        let err = Description::new("", "name").unwrap_err();
+        assert_eq!(err, errno::Errno(libc::EINVAL));
+    }
+
+    #[test]
+    fn test_logon_description_parse() {
+        let desc = Description::parse("service:name:with:colons").unwrap();
+        assert_eq!(desc.subtype(), "service");
+        assert_eq!(desc.name(), "name:with:colons");
+
+        let invalid = errno::Errno(libc::EINVAL);
+        assert_eq!(Description::parse("service").unwrap_err(), invalid);
+        assert_eq!(Description::parse(":name").unwrap_err(), invalid);
+    }
+}
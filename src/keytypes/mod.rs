@@ -58,6 +58,9 @@ pub use self::rxrpc::RxRPC;
 pub mod rxrpc_s;
 pub use self::rxrpc_s::RxRPCServer;
 
+pub mod structured;
+pub use self::structured::StructuredDescription;
+
 pub mod trusted;
 pub use self::trusted::Trusted;
 
@@ -65,7 +68,7 @@ pub mod user;
 pub use self::user::User;
 
 /// A structure for assisting in display binary data.
-struct ByteBuf<'a>(&'a [u8]);
+pub(crate) struct ByteBuf<'a>(pub(crate) &'a [u8]);
 
 impl fmt::LowerHex for ByteBuf<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -76,9 +79,40 @@ impl fmt::LowerHex for ByteBuf<'_> {
     }
 }
 
+/// Decode ASCII hex data as used by the kernel for binary blobs.
+///
+/// Returns `None` if the data is not valid hex.
+pub(crate) fn from_hex(hex: &[u8]) -> Option<Vec<u8>> {
+    fn nibble(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let pairs = hex.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| Some((nibble(pair[0])? << 4) | nibble(pair[1])?))
+        .collect()
+}
+
+/// A placeholder for secret material in `Debug` output.
+pub(crate) struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ByteBuf;
+    use super::{from_hex, ByteBuf};
 
     fn check(input: &[u8], expected: &str) {
         assert_eq!(format!("{:x}", ByteBuf(input)), expected);
@@ -90,4 +124,13 @@ mod tests {
         check(&[0, 1], "0001");
         check(&[222, 173, 190, 239], "deadbeef");
     }
+
+    #[test]
+    fn test_ascii_hex_decode() {
+        assert_eq!(from_hex(b""), Some(Vec::new()));
+        assert_eq!(from_hex(b"0001"), Some(vec![0, 1]));
+        assert_eq!(from_hex(b"deadBEEF"), Some(vec![222, 173, 190, 239]));
+        assert_eq!(from_hex(b"abc"), None);
+        assert_eq!(from_hex(b"zz"), None);
+    }
 }
@@ -27,7 +27,9 @@
 //! Keys for RxRPC clients.
 
 use std::borrow::Cow;
+use std::fmt;
 
+use super::Redacted;
 use crate::keytype::*;
 
 /// An RxRPC client key.
@@ -45,7 +47,9 @@ impl KeyType for RxRPC {
 }
 
 /// The payload for RxRPC client keys.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// The session key is redacted from the `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Payload {
     expiry: u32,
     version: u8,
@@ -53,6 +57,17 @@ pub struct Payload {
     ticket: Vec<u8>,
 }
 
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Payload")
+            .field("expiry", &self.expiry)
+            .field("version", &self.version)
+            .field("session_key", &Redacted)
+            .field("ticket", &self.ticket)
+            .finish()
+    }
+}
+
 impl KeyPayload for Payload {
     fn payload(&self) -> Cow<[u8]> {
         let mut payload = Vec::new();
@@ -78,3 +93,22 @@ impl KeyPayload for Payload {
         payload.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Payload;
+
+    #[test]
+    fn test_payload_debug_redacted() {
+        let payload = Payload {
+            expiry: 1,
+            version: 2,
+            session_key: [0xab; 8],
+            ticket: vec![3],
+        };
+
+        let debug = format!("{:?}", payload);
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains("171"));
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Structured descriptions
+//!
+//! Many keys use descriptions made of `:`-separated segments, starting with the service which
+//! owns the key (e.g., `service:subtype:identifier`).
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::keytype::*;
+use crate::keytypes::logon;
+use crate::Result;
+
+/// A description made of non-empty, `:`-separated segments.
+///
+/// There are at least two segments, the first of which is the service. Segments may not contain
+/// `:` or NUL bytes. This may be used as the description of any key type with free-form
+/// descriptions (e.g., `User` via `as_str`) and converted into a `logon` description.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructuredDescription {
+    description: String,
+}
+
+impl StructuredDescription {
+    /// Create a description from its segments.
+    ///
+    /// Returns `EINVAL` if there are fewer than two segments or any segment is empty or contains
+    /// `:` or a NUL byte.
+    pub fn new<I, S>(segments: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let segments = segments
+            .into_iter()
+            .map(|segment| {
+                let segment = segment.as_ref();
+                if segment.contains(':') {
+                    Err(errno::Errno(libc::EINVAL))
+                } else {
+                    Ok(segment.to_string())
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::parse(&segments.join(":"))
+    }
+
+    /// Parse a description in `service:segment[:segment...]` format.
+    ///
+    /// Returns `EINVAL` if there are fewer than two segments or any segment is empty or contains
+    /// a NUL byte.
+    pub fn parse(desc: &str) -> Result<Self> {
+        let mut segments = desc.split(':');
+        let valid = segments.clone().count() >= 2
+            && segments.all(|segment| !segment.is_empty() && !segment.contains('\0'));
+        if !valid {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+        Ok(StructuredDescription {
+            description: desc.to_string(),
+        })
+    }
+
+    /// The service which owns the key.
+    pub fn service(&self) -> &str {
+        self.segments()
+            .next()
+            .expect("structured descriptions have at least two segments")
+    }
+
+    /// The segments of the description.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.description.split(':')
+    }
+
+    /// The description as a string.
+    pub fn as_str(&self) -> &str {
+        &self.description
+    }
+}
+
+impl AsRef<str> for StructuredDescription {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for StructuredDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl FromStr for StructuredDescription {
+    type Err = errno::Errno;
+
+    fn from_str(desc: &str) -> Result<Self> {
+        Self::parse(desc)
+    }
+}
+
+impl KeyDescription for StructuredDescription {
+    fn description(&self) -> Cow<str> {
+        Cow::Borrowed(&self.description)
+    }
+}
+
+impl From<StructuredDescription> for logon::Description {
+    /// The service becomes the subtype of the logon key and the remaining segments its
+    /// description.
+    fn from(desc: StructuredDescription) -> Self {
+        let mut parts = desc.description.splitn(2, ':');
+        let subtype = parts.next().unwrap_or_default().to_string();
+        let description = parts.next().unwrap_or_default().to_string();
+        logon::Description::new(subtype, description)
+            .expect("structured descriptions have a non-empty service")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::StructuredDescription;
+    use crate::keytype::KeyDescription;
+    use crate::keytypes::logon;
+
+    #[test]
+    fn test_structured_description() {
+        let desc = StructuredDescription::new(["test", "ruskey", "add_key"]).unwrap();
+        assert_eq!(desc.as_str(), "test:ruskey:add_key");
+        assert_eq!(desc.description(), Cow::Borrowed("test:ruskey:add_key"));
+        assert_eq!(desc.service(), "test");
+        assert_eq!(
+            desc.segments().collect::<Vec<_>>(),
+            ["test", "ruskey", "add_key"],
+        );
+        assert_eq!(desc.to_string(), "test:ruskey:add_key");
+        assert_eq!(
+            "test:ruskey:add_key"
+                .parse::<StructuredDescription>()
+                .unwrap(),
+            desc,
+        );
+    }
+
+    #[test]
+    fn test_structured_description_invalid() {
+        let invalid = errno::Errno(libc::EINVAL);
+        assert_eq!(StructuredDescription::parse("").unwrap_err(), invalid);
+        assert_eq!(
+            StructuredDescription::parse("service").unwrap_err(),
+            invalid
+        );
+        assert_eq!(StructuredDescription::parse(":name").unwrap_err(), invalid);
+        assert_eq!(
+            StructuredDescription::parse("service:").unwrap_err(),
+            invalid
+        );
+        assert_eq!(StructuredDescription::parse("a::b").unwrap_err(), invalid);
+        assert_eq!(StructuredDescription::parse("a:b\0c").unwrap_err(), invalid);
+        assert_eq!(
+            StructuredDescription::new(["a", "b:c"]).unwrap_err(),
+            invalid,
+        );
+    }
+
+    #[test]
+    fn test_structured_description_logon() {
+        let desc = StructuredDescription::parse("cifs:user:name").unwrap();
+        let logon = logon::Description::from(desc);
+        assert_eq!(logon.subtype(), "cifs");
+        assert_eq!(logon.name(), "user:name");
+    }
+}
@@ -29,8 +29,9 @@
 use std::borrow::Cow;
 use std::fmt;
 
-use super::ByteBuf;
+use super::{ByteBuf, Redacted};
 use crate::keytype::*;
+use crate::{Key, Result};
 
 /// Trusted keys are rooted in the TPM.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -83,7 +84,9 @@ impl TpmHash {
 }
 
 /// Options for trusted keys.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+///
+/// The authorization values are redacted from the `Debug` output.
+#[derive(Default, Clone, PartialEq, Eq)]
 pub struct TrustedOptions {
     /// The ID of the sealing key to use.
     pub keyhandle: Option<u32>,
@@ -111,6 +114,22 @@ pub struct TrustedOptions {
     pub policyhandle: Option<u32>,
 }
 
+impl fmt::Debug for TrustedOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TrustedOptions")
+            .field("keyhandle", &self.keyhandle)
+            .field("keyauth", &self.keyauth.as_ref().map(|_| Redacted))
+            .field("blobauth", &self.blobauth.as_ref().map(|_| Redacted))
+            .field("pcrinfo", &self.pcrinfo)
+            .field("pcrlock", &self.pcrlock)
+            .field("migratable", &self.migratable)
+            .field("hash", &self.hash)
+            .field("policydigest", &self.policydigest)
+            .field("policyhandle", &self.policyhandle)
+            .finish()
+    }
+}
+
 impl fmt::Display for TrustedOptions {
     /// Formats the options that are present. Starts with a leading space.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -213,3 +232,82 @@ impl KeyPayload for Payload {
         .into()
     }
 }
+
+/// Read the sealed blob of a trusted key.
+///
+/// The kernel only exposes the blob sealed by the TPM; the cleartext key material never leaves
+/// the kernel. The returned blob may be passed to `Payload::Load` to load the key again. Returns
+/// `EINVAL` if the key's payload is not a hex-encoded blob. Requires `read` permission on the
+/// key.
+pub fn read_blob(key: &Key) -> Result<Vec<u8>> {
+    let hex = key.read()?;
+    super::from_hex(&hex).ok_or(errno::Errno(libc::EINVAL))
+}
+
+impl GeneratableKeyType for Trusted {
+    type Options = TrustedOptions;
+
+    fn generate_payload(len: usize, options: &Self::Options) -> Box<Self::Payload> {
+        Box::new(Payload::New {
+            keylen: len,
+            options: options.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::KeyPayload;
+
+    use super::{Payload, TrustedOptions};
+
+    fn check(payload: Payload, expected: &str) {
+        assert_eq!(payload.payload(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_payload_commands() {
+        check(
+            Payload::New {
+                keylen: 32,
+                options: TrustedOptions {
+                    keyhandle: Some(0x8100_0001),
+                    ..Default::default()
+                },
+            },
+            "new 32 keyhandle=81000001",
+        );
+        check(
+            Payload::Load {
+                blob: vec![0xde, 0xad, 0xbe, 0xef],
+                options: TrustedOptions::default(),
+            },
+            "load deadbeef",
+        );
+        check(
+            Payload::Update {
+                options: TrustedOptions {
+                    pcrlock: Some(7),
+                    ..Default::default()
+                },
+            },
+            "update pcrlock=7",
+        );
+    }
+
+    #[test]
+    fn test_options_debug_redacted() {
+        let options = TrustedOptions {
+            keyhandle: Some(0x4000_0000),
+            keyauth: Some([0xab; 20]),
+            blobauth: Some([0xcd; 20]),
+            ..Default::default()
+        };
+
+        let debug = format!("{:?}", options);
+        assert!(debug.contains("redacted"));
+        assert!(debug.contains("1073741824"));
+        assert!(!debug.contains("171"));
+        assert!(!debug.contains("205"));
+    }
+}
@@ -29,6 +29,7 @@
 use keyutils_raw::KEY_TYPE_USER;
 
 use crate::keytype::*;
+use crate::Result;
 
 /// Keys which can be created, updated, and read from userspace but are not intended for use by the
 /// kernel.
@@ -45,3 +46,12 @@ impl KeyType for User {
         KEY_TYPE_USER
     }
 }
+
+impl ReadableKeyType for User {
+    /// User payloads are read back verbatim.
+    type Output = Vec<u8>;
+
+    fn parse_payload(payload: &[u8]) -> Result<Self::Output> {
+        Ok(payload.to_vec())
+    }
+}
@@ -31,14 +31,22 @@
 #![warn(missing_docs)]
 
 mod api;
+mod capabilities;
 mod constants;
+mod error;
 mod keytype;
+mod watch;
 
 pub mod keytypes;
+pub mod proc;
+pub mod upcall;
 
 pub use self::api::*;
+pub use self::capabilities::*;
 pub use self::constants::*;
+pub use self::error::*;
 pub use self::keytype::*;
+pub use self::watch::*;
 
 pub use keyutils_raw::{DefaultKeyring, KeyPermissions, KeyringSerial, TimeoutSeconds};
 
@@ -0,0 +1,394 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Parsing of `/proc/keys`
+//!
+//! The kernel lists all keys which the current process may view in `/proc/keys`. This exposes
+//! information which is otherwise unavailable such as the time remaining before a key expires
+//! and its usage count.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+use bitflags::bitflags;
+
+use crate::{Keyring, KeyringSerial, Permission, Result, SpecialKeyring};
+
+const PROC_KEYS: &str = "/proc/keys";
+
+bitflags! {
+    /// State flags for a key as listed in `/proc/keys`.
+    pub struct ProcKeyFlags: u8 {
+        /// The key has been instantiated.
+        const INSTANTIATED          = 0x01;
+        /// The key has been revoked.
+        const REVOKED               = 0x02;
+        /// The key is dead and awaiting garbage collection.
+        const DEAD                  = 0x04;
+        /// The key contributes to the user's quota.
+        const QUOTA                 = 0x08;
+        /// The key is under construction by a callout.
+        const UNDER_CONSTRUCTION    = 0x10;
+        /// The key is negatively instantiated.
+        const NEGATIVE              = 0x20;
+        /// The key has been invalidated.
+        const INVALIDATED           = 0x40;
+    }
+}
+
+// The flag characters in the order the kernel prints them.
+const FLAG_CHARS: [(char, ProcKeyFlags); 7] = [
+    ('I', ProcKeyFlags::INSTANTIATED),
+    ('R', ProcKeyFlags::REVOKED),
+    ('D', ProcKeyFlags::DEAD),
+    ('Q', ProcKeyFlags::QUOTA),
+    ('U', ProcKeyFlags::UNDER_CONSTRUCTION),
+    ('N', ProcKeyFlags::NEGATIVE),
+    ('i', ProcKeyFlags::INVALIDATED),
+];
+
+/// The expiration state of a key as listed in `/proc/keys`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcKeyTimeout {
+    /// The key does not expire.
+    Permanent,
+    /// The key has expired.
+    Expired,
+    /// The key expires in at least the given amount of time.
+    ///
+    /// The kernel only reports the time in the largest whole unit (seconds, minutes, hours, days,
+    /// or weeks) which fits, so the actual remaining time may be up to one unit longer.
+    Remaining(Duration),
+}
+
+impl ProcKeyTimeout {
+    fn parse(timeout: &str) -> Option<Self> {
+        match timeout {
+            "perm" => return Some(ProcKeyTimeout::Permanent),
+            "expd" => return Some(ProcKeyTimeout::Expired),
+            _ => (),
+        }
+
+        if timeout.len() < 2 {
+            return None;
+        }
+        let (value, unit) = timeout.split_at(timeout.len() - 1);
+        let scale = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 60 * 60 * 24,
+            "w" => 60 * 60 * 24 * 7,
+            _ => return None,
+        };
+        value
+            .parse::<u64>()
+            .ok()
+            .map(|value| ProcKeyTimeout::Remaining(Duration::from_secs(value * scale)))
+    }
+}
+
+/// The exclusive upper bound of a remaining time reported by the kernel.
+///
+/// The kernel rounds the remaining time down to the largest unit which fits, so the actual time
+/// may be up to one of that unit longer than `remaining`.
+pub(crate) fn remaining_upper_bound(remaining: Duration) -> Duration {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const WEEK: u64 = 7 * DAY;
+
+    let unit = match remaining.as_secs() {
+        secs if secs < MINUTE => 1,
+        secs if secs < HOUR => MINUTE,
+        secs if secs < DAY => HOUR,
+        secs if secs < WEEK => DAY,
+        _ => WEEK,
+    };
+    remaining + Duration::from_secs(unit)
+}
+
+/// The timeout to set to cap a key expiring after `remaining` (as reported) at `max`.
+///
+/// Returns `None` if the key is known to expire within `max`. Otherwise, the lower of `max` and
+/// `remaining` (at least one second) is used so that the timeout is never extended.
+pub(crate) fn capped_timeout(remaining: Option<Duration>, max: Duration) -> Option<Duration> {
+    match remaining {
+        Some(remaining) if remaining_upper_bound(remaining) <= max => None,
+        Some(remaining) => Some(max.min(remaining.max(Duration::from_secs(1)))),
+        None => Some(max),
+    }
+}
+
+/// An entry in `/proc/keys`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcKey {
+    /// The serial of the key.
+    pub serial: KeyringSerial,
+    /// The state of the key.
+    pub flags: ProcKeyFlags,
+    /// The number of references to the key.
+    pub usage: u32,
+    /// When the key expires.
+    pub timeout: ProcKeyTimeout,
+    /// The permissions of the key.
+    pub perms: Permission,
+    /// The user owner of the key.
+    pub uid: libc::uid_t,
+    /// The group owner of the key.
+    pub gid: libc::gid_t,
+    /// The type of the key.
+    ///
+    /// Note that the kernel truncates this to 9 characters.
+    pub type_: String,
+    /// The description of the key as rendered by its key type.
+    ///
+    /// Most key types append a summary of the payload to the description (e.g., the size of the
+    /// payload or the number of keys in a keyring). Bytes which are not valid UTF-8 are replaced
+    /// with `U+FFFD`.
+    pub description: String,
+}
+
+impl ProcKey {
+    /// Parse a line from `/proc/keys`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line;
+        let mut field = || {
+            let trimmed = rest.trim_start();
+            let end = trimmed.find(' ').unwrap_or(trimmed.len());
+            let (field, tail) = trimmed.split_at(end);
+            rest = tail;
+            field
+        };
+
+        let serial = i32::from_str_radix(field(), 16)
+            .ok()
+            .and_then(KeyringSerial::new)?;
+        let flag_chars = field();
+        if flag_chars.len() != FLAG_CHARS.len() {
+            return None;
+        }
+        let flags = flag_chars
+            .chars()
+            .zip(FLAG_CHARS.iter())
+            .filter(|&(actual, &(expected, _))| actual == expected)
+            .fold(ProcKeyFlags::empty(), |flags, (_, &(_, flag))| flags | flag);
+        let usage = field().parse().ok()?;
+        let timeout = ProcKeyTimeout::parse(field())?;
+        let perms = Permission::from_raw(u32::from_str_radix(field(), 16).ok()?);
+        let uid = field().parse().ok()?;
+        let gid = field().parse().ok()?;
+        let type_ = field().into();
+        let description = rest.trim_start().into();
+
+        Some(ProcKey {
+            serial,
+            flags,
+            usage,
+            timeout,
+            perms,
+            uid,
+            gid,
+            type_,
+            description,
+        })
+    }
+}
+
+fn io_error(err: io::Error) -> errno::Errno {
+    errno::Errno(err.raw_os_error().unwrap_or(libc::EIO))
+}
+
+/// Read all keys listed in `/proc/keys`.
+///
+/// Only keys which the current process may view are listed. Lines which cannot be parsed are
+/// skipped.
+pub fn keys() -> Result<Vec<ProcKey>> {
+    // Key descriptions may contain any byte other than NUL.
+    let data = fs::read(PROC_KEYS).map_err(io_error)?;
+    Ok(String::from_utf8_lossy(&data)
+        .lines()
+        .filter_map(ProcKey::parse)
+        .collect())
+}
+
+/// Find the entry for a key in `/proc/keys`.
+///
+/// Returns `ENOKEY` if the key is not listed.
+pub fn key(serial: KeyringSerial) -> Result<ProcKey> {
+    keys()?
+        .into_iter()
+        .find(|key| key.serial == serial)
+        .ok_or(errno::Errno(libc::ENOKEY))
+}
+
+/// Find keys owned by the current user which are not reachable from the current process.
+///
+/// All keyrings reachable from the thread, process, session, user, and user session keyrings are
+/// walked and any key listed in `/proc/keys` which was not found is returned.
+///
+/// Only keys which the process may view are listed in `/proc/keys` and only keyrings which the
+/// process may read can be walked. Keys linked only from keyrings which may not be read are
+/// reported as orphaned even though they are reachable.
+pub fn orphaned_keys() -> Result<Vec<ProcKey>> {
+    let keys = keys()?;
+    let uid = unsafe { libc::geteuid() };
+    let types = keys
+        .iter()
+        .map(|key| (key.serial, key.type_.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let mut pending = [
+        SpecialKeyring::Thread,
+        SpecialKeyring::Process,
+        SpecialKeyring::Session,
+        SpecialKeyring::User,
+        SpecialKeyring::UserSession,
+    ]
+    .iter()
+    .filter_map(|&special| Keyring::attach(special).ok())
+    .map(|keyring| keyring.serial())
+    .collect::<Vec<_>>();
+    let mut reachable = HashSet::new();
+    while let Some(serial) = pending.pop() {
+        if !reachable.insert(serial) {
+            continue;
+        }
+        // Only keyrings may be walked; reading any other key would return its payload.
+        if types.get(&serial) != Some(&"keyring") {
+            continue;
+        }
+        if let Ok(children) = Keyring::new_impl(serial).read_serials() {
+            pending.extend(children);
+        }
+    }
+
+    Ok(keys
+        .into_iter()
+        .filter(|key| key.uid == uid && !reachable.contains(&key.serial))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{capped_timeout, remaining_upper_bound, ProcKey, ProcKeyFlags, ProcKeyTimeout};
+    use crate::Permission;
+
+    #[test]
+    fn test_parse_timeout() {
+        let check = |timeout, expected| assert_eq!(ProcKeyTimeout::parse(timeout), expected);
+        let remaining = |secs| Some(ProcKeyTimeout::Remaining(Duration::from_secs(secs)));
+
+        check("perm", Some(ProcKeyTimeout::Permanent));
+        check("expd", Some(ProcKeyTimeout::Expired));
+        check("59s", remaining(59));
+        check("1m", remaining(60));
+        check("2h", remaining(2 * 60 * 60));
+        check("3d", remaining(3 * 60 * 60 * 24));
+        check("4w", remaining(4 * 60 * 60 * 24 * 7));
+        check("", None);
+        check("s", None);
+        check("10y", None);
+    }
+
+    #[test]
+    fn test_capped_timeout() {
+        let secs = Duration::from_secs;
+        let check = |remaining: Option<u64>, max, expected: Option<u64>| {
+            assert_eq!(
+                capped_timeout(remaining.map(secs), secs(max)),
+                expected.map(secs),
+            )
+        };
+
+        // Permanent keys are capped.
+        check(None, 100, Some(100));
+        // Keys known to expire within `max` are left alone.
+        check(Some(59), 60, None);
+        check(Some(60), 120, None);
+        // Keys which may expire after `max` are capped without extending them.
+        check(Some(60), 100, Some(60));
+        check(Some(60), 30, Some(30));
+        check(Some(5 * 60), 5 * 60 + 30, Some(5 * 60));
+        check(Some(0), 0, Some(0));
+    }
+
+    #[test]
+    fn test_remaining_upper_bound() {
+        let check = |secs, expected| {
+            assert_eq!(
+                remaining_upper_bound(Duration::from_secs(secs)),
+                Duration::from_secs(expected),
+            )
+        };
+
+        check(0, 1);
+        check(59, 60);
+        check(60, 2 * 60);
+        check(59 * 60, 60 * 60);
+        check(60 * 60, 2 * 60 * 60);
+        check(60 * 60 * 24, 2 * 60 * 60 * 24);
+        check(6 * 60 * 60 * 24, 7 * 60 * 60 * 24);
+        check(60 * 60 * 24 * 7, 2 * 60 * 60 * 24 * 7);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let line = "1cd2406d I--Q---     1  59m 3f010000  1000   100 user      \
+                    desc: with: colons: 7";
+        let key = ProcKey::parse(line).unwrap();
+
+        assert_eq!(key.serial.get(), 0x1cd2_406d);
+        assert_eq!(key.flags, ProcKeyFlags::INSTANTIATED | ProcKeyFlags::QUOTA);
+        assert_eq!(key.usage, 1);
+        assert_eq!(
+            key.timeout,
+            ProcKeyTimeout::Remaining(Duration::from_secs(59 * 60)),
+        );
+        assert_eq!(key.perms, Permission::POSSESSOR_ALL | Permission::USER_VIEW);
+        assert_eq!(key.uid, 1000);
+        assert_eq!(key.gid, 100);
+        assert_eq!(key.type_, "user");
+        assert_eq!(key.description, "desc: with: colons: 7");
+    }
+
+    #[test]
+    fn test_parse_line_invalid() {
+        assert_eq!(ProcKey::parse(""), None);
+        assert_eq!(
+            ProcKey::parse("00000000 I--Q---     1 perm 3f010000  1000   100 user      desc: 7"),
+            None,
+        );
+        assert_eq!(
+            ProcKey::parse("1cd2406d I--Q--     1 perm 3f010000  1000   100 user      desc: 7"),
+            None,
+        );
+    }
+}
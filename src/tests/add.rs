@@ -25,8 +25,11 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::iter;
+use std::time::Duration;
 
-use crate::keytypes::User;
+use crate::keytypes::encrypted::{self, GenerateOptions, MasterKeyType};
+use crate::keytypes::{self, BigKey, Encrypted, User};
+use crate::{KeyOutcome, Keyring, Permission};
 
 use super::utils;
 use super::utils::kernel::*;
@@ -162,6 +165,82 @@ fn add_keyring() {
     assert!(keyrings.is_empty());
 }
 
+#[test]
+fn add_keyring_nested() {
+    let mut keyring = utils::new_test_keyring();
+    let mut outer = keyring.add_keyring("add_keyring_nested_outer").unwrap();
+    let inner = outer.add_keyring("add_keyring_nested_inner").unwrap();
+
+    let (keys, keyrings) = outer.read().unwrap();
+    assert!(keys.is_empty());
+    assert_eq!(keyrings.len(), 1);
+    assert_eq!(keyrings[0], inner);
+
+    let (keys, keyrings) = inner.read().unwrap();
+    assert!(keys.is_empty());
+    assert!(keyrings.is_empty());
+}
+
+#[test]
+fn add_key_with_permissions() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let perms = Permission::POSSESSOR_VIEW
+        | Permission::POSSESSOR_READ
+        | Permission::POSSESSOR_SEARCH
+        | Permission::USER_VIEW;
+    let key = keyring
+        .add_key_with_permissions::<User, _, _>("add_key_with_permissions", payload, perms)
+        .unwrap();
+    assert_eq!(key.description().unwrap().perms, perms);
+    assert_eq!(key.read().unwrap(), payload);
+}
+
+#[test]
+fn add_key_locked() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key_locked::<User, _, _>("add_key_locked", payload)
+        .unwrap();
+    assert_eq!(
+        key.description().unwrap().perms,
+        Permission::POSSESSOR_ALL | Permission::USER_VIEW,
+    );
+    assert_eq!(key.read().unwrap(), payload);
+}
+
+#[test]
+fn add_key_nul_description() {
+    let mut keyring = utils::new_test_keyring();
+    let err = keyring
+        .add_key::<User, _, _>("add_key\0nul", &b"payload"[..])
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn try_add_key() {
+    let mut keyring = utils::new_test_keyring();
+    let description = "try_add_key";
+
+    let payload = &b"payload"[..];
+    let key = keyring
+        .try_add_key::<User, _, _>(description, payload)
+        .unwrap();
+
+    let err = keyring
+        .try_add_key::<User, _, _>(description, &b"updated_payload"[..])
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EEXIST));
+    assert_eq!(key.read().unwrap(), payload);
+
+    // Keys of other types with the same description do not collide.
+    keyring
+        .try_add_key::<keytypes::Keyring, _, _>(description, ())
+        .unwrap();
+}
+
 #[test]
 fn add_key_replace() {
     let mut keyring = utils::new_test_keyring();
@@ -179,6 +258,58 @@ fn add_key_replace() {
     assert_eq!(key_updated.read().unwrap(), payload);
 }
 
+#[test]
+fn add_key_verbose() {
+    let mut keyring = utils::new_test_keyring();
+
+    let description = "add_key_verbose";
+
+    let payload = &b"payload"[..];
+    let (key, outcome) = keyring
+        .add_key_verbose::<User, _, _>(description, payload)
+        .unwrap();
+    assert_eq!(outcome, KeyOutcome::Created);
+
+    let payload = &b"updated_payload"[..];
+    let (key_updated, outcome) = keyring
+        .add_key_verbose::<User, _, _>(description, payload)
+        .unwrap();
+    assert_eq!(outcome, KeyOutcome::Updated);
+    assert_eq!(key, key_updated);
+    assert_eq!(key.read().unwrap(), payload);
+}
+
+#[test]
+fn key_builder() {
+    let mut keyring = utils::new_test_keyring();
+
+    let perms = Permission::POSSESSOR_VIEW
+        | Permission::POSSESSOR_READ
+        | Permission::POSSESSOR_SEARCH
+        | Permission::USER_VIEW;
+    let key = Keyring::key_builder::<User>()
+        .description("key_builder")
+        .payload(&b"payload"[..])
+        .timeout(Duration::from_secs(60))
+        .permissions(perms)
+        .add_to(&mut keyring)
+        .unwrap();
+
+    assert_eq!(key.read().unwrap(), b"payload");
+    assert_eq!(key.description().unwrap().perms, perms);
+    assert!(key.timeout_remaining().unwrap().is_some());
+}
+
+#[test]
+fn key_builder_missing_payload() {
+    let mut keyring = utils::new_test_keyring();
+    let err = Keyring::key_builder::<User>()
+        .description("key_builder_missing_payload")
+        .add_to(&mut keyring)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
 #[test]
 fn add_keyring_replace() {
     let mut keyring = utils::new_test_keyring();
@@ -197,3 +328,113 @@ fn add_keyring_replace() {
     assert!(keys.is_empty());
     assert!(keyrings.is_empty());
 }
+
+#[test]
+fn generate_encrypted_key() {
+    let mut keyring = utils::new_test_keyring();
+    let master = &[0x42; 32][..];
+    keyring
+        .add_key::<User, _, _>("generate_encrypted_key_master", master)
+        .unwrap();
+
+    let options = GenerateOptions {
+        format: None,
+        keytype: MasterKeyType::User,
+        description: "generate_encrypted_key_master".into(),
+    };
+    let key = match keyring.generate_key::<Encrypted, _>("generate_encrypted_key", 256, &options) {
+        Ok(key) => key,
+        Err(errno::Errno(libc::ENODEV)) => {
+            eprintln!("This kernel does not support encrypted keys; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to generate an encrypted key: {}", err),
+    };
+
+    let blob = key.read().unwrap();
+    assert!(!blob.is_empty());
+}
+
+#[test]
+fn load_encrypted_key() {
+    let mut keyring = utils::new_test_keyring();
+    let master = &[0x42; 32][..];
+    keyring
+        .add_key::<User, _, _>("load_encrypted_key_master", master)
+        .unwrap();
+
+    let payload = encrypted::Payload::New {
+        format: None,
+        keytype: MasterKeyType::User,
+        description: "load_encrypted_key_master".into(),
+        keylen: 32,
+    };
+    let key = match keyring.add_key::<Encrypted, _, _>("load_encrypted_key", &payload) {
+        Ok(key) => key,
+        Err(errno::Errno(libc::ENODEV)) => {
+            eprintln!("This kernel does not support encrypted keys; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to add an encrypted key: {}", err),
+    };
+    let blob = key.read().unwrap();
+    assert!(blob.starts_with(b"default user:load_encrypted_key_master 32 "));
+
+    // Load the blob into a fresh keyring.
+    let mut fresh = keyring.add_keyring("load_encrypted_key_fresh").unwrap();
+    let payload = encrypted::Payload::Load {
+        blob: blob.clone(),
+    };
+    let loaded = fresh
+        .add_key::<Encrypted, _, _>("load_encrypted_key", &payload)
+        .unwrap();
+    assert_ne!(loaded, key);
+    assert_eq!(loaded.read().unwrap(), blob);
+}
+
+#[test]
+fn generate_key_partial_bytes() {
+    let mut keyring = utils::new_test_keyring();
+    let options = GenerateOptions {
+        format: None,
+        keytype: MasterKeyType::User,
+        description: "generate_key_partial_bytes_master".into(),
+    };
+
+    let err = keyring
+        .generate_key::<Encrypted, _>("generate_key_partial_bytes", 255, &options)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+// The largest payload the kernel accepts through `add_key`.
+const BIG_KEY_MAX_SIZE: usize = 1024 * 1024 - 1;
+
+#[test]
+fn add_big_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = (0..BIG_KEY_MAX_SIZE).map(|i| i as u8).collect::<Vec<_>>();
+    let key = match keyring.add_key::<BigKey, _, _>("add_big_key", &payload[..]) {
+        Ok(key) => key,
+        Err(errno::Errno(libc::ENODEV)) => {
+            eprintln!("This kernel does not support big keys; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to add a big key: {}", err),
+    };
+
+    assert_eq!(key.read().unwrap(), payload);
+    key.invalidate().unwrap();
+}
+
+#[test]
+fn add_big_key_too_large() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = vec![0; BIG_KEY_MAX_SIZE + 1];
+    match keyring.add_key::<BigKey, _, _>("add_big_key_too_large", &payload[..]) {
+        Err(errno::Errno(libc::ENODEV)) => {
+            eprintln!("This kernel does not support big keys; skipping.");
+        },
+        res => assert_eq!(res.unwrap_err(), errno::Errno(libc::EINVAL)),
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::{capabilities, supports_notifications};
+
+use super::utils;
+
+#[test]
+fn query_capabilities() {
+    let caps = capabilities().unwrap();
+    assert_eq!(caps.supports_notifications(), supports_notifications());
+}
+
+#[test]
+fn move_capability() {
+    let caps = capabilities().unwrap();
+
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_capability_from").unwrap();
+    let mut to = keyring.add_keyring("move_capability_to").unwrap();
+    let key = from
+        .add_key::<User, _, _>("move_capability", &b"payload"[..])
+        .unwrap();
+
+    let res = from.move_key(&key, &mut to, false);
+    if caps.supports_move() {
+        res.unwrap();
+    } else {
+        assert_eq!(res.unwrap_err(), errno::Errno(libc::EOPNOTSUPP));
+    }
+}
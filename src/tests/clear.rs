@@ -25,7 +25,7 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::keytypes::User;
-use crate::Keyring;
+use crate::{Key, Keyring};
 
 use super::utils;
 
@@ -179,3 +179,37 @@ fn clear_keyring_keyring() {
     assert_eq!(keys.len(), 0);
     assert_eq!(keyrings.len(), 0);
 }
+
+#[test]
+fn clear_recursive() {
+    let mut keyring = utils::new_test_keyring();
+    let mut other = utils::new_test_keyring();
+
+    let mut owned = keyring.add_keyring("clear_recursive:owned").unwrap();
+    let nested = owned.add_keyring("clear_recursive:nested").unwrap();
+    let mut shared = keyring.add_keyring("clear_recursive:shared").unwrap();
+    let inner = shared.add_keyring("clear_recursive:inner").unwrap();
+    other.link_keyring(&shared).unwrap();
+
+    keyring.clear_recursive().unwrap();
+
+    let (keys, keyrings) = keyring.read().unwrap();
+    assert_eq!(keys.len(), 0);
+    assert_eq!(keyrings.len(), 0);
+
+    // Keyrings only linked from within the tree are invalidated.
+    utils::wait_for_keyring_gc(&owned);
+    utils::wait_for_keyring_gc(&nested);
+    // Shared keyrings are only unlinked.
+    assert_eq!(
+        shared.description().unwrap().description,
+        "clear_recursive:shared",
+    );
+    assert!(other.contains(&Key::from(shared.clone())).unwrap());
+    // Keyrings nested within shared keyrings are left alone.
+    assert_eq!(
+        inner.description().unwrap().description,
+        "clear_recursive:inner",
+    );
+    assert!(shared.contains(&Key::from(inner)).unwrap());
+}
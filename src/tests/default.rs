@@ -0,0 +1,61 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::panic;
+
+use crate::{DefaultKeyring, Keyring};
+
+fn current_default() -> DefaultKeyring {
+    Keyring::set_default(DefaultKeyring::NoChange).unwrap()
+}
+
+fn other_default(old: &DefaultKeyring) -> DefaultKeyring {
+    if *old == DefaultKeyring::ThreadKeyring {
+        DefaultKeyring::ProcessKeyring
+    } else {
+        DefaultKeyring::ThreadKeyring
+    }
+}
+
+#[test]
+fn with_default() {
+    let old = current_default();
+
+    let inner = Keyring::with_default(other_default(&old), current_default).unwrap();
+    assert_eq!(inner, other_default(&old));
+    assert_eq!(current_default(), old);
+}
+
+#[test]
+fn with_default_panic() {
+    let old = current_default();
+
+    let res = panic::catch_unwind(|| {
+        Keyring::with_default(other_default(&old), || panic!("with_default_panic")).unwrap();
+    });
+    assert!(res.is_err());
+    assert_eq!(current_default(), old);
+}
@@ -25,11 +25,46 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::keytypes::{Keyring, User};
-use crate::{Key, KeyType, Permission};
+use crate::{
+    proc, Category, Description, DescriptionParseError, Key, KeyType, KeyringSerial, Permission,
+};
 
 use super::utils;
 use super::utils::kernel::*;
 
+#[test]
+fn non_utf8_description() {
+    let keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    // The kernel accepts descriptions with any bytes other than NUL, but the API only creates
+    // keys with UTF-8 descriptions.
+    let serial = unsafe {
+        libc::syscall(
+            libc::SYS_add_key,
+            b"user\0".as_ptr(),
+            b"non_utf8_description\xff\0".as_ptr(),
+            payload.as_ptr(),
+            payload.len(),
+            keyring.serial().get(),
+        )
+    };
+    assert!(serial > 0);
+    let key = unsafe { Key::new(KeyringSerial::new(serial as i32).unwrap()) };
+
+    let eilseq = errno::Errno(libc::EILSEQ);
+    assert_eq!(key.describe_raw().unwrap_err(), eilseq);
+    assert_eq!(key.description().unwrap_err(), eilseq);
+    assert_eq!(keyring.read().unwrap_err(), eilseq);
+
+    // `/proc/keys` is still readable.
+    let entry = proc::key(key.serial()).unwrap();
+    assert!(entry
+        .description
+        .starts_with("non_utf8_description\u{fffd}"));
+
+    key.invalidate().unwrap();
+}
+
 #[test]
 fn invalid_key() {
     let key = utils::invalid_key();
@@ -125,3 +160,131 @@ fn describe_revoked_key() {
     let err = key_mirror.description().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
 }
+
+#[test]
+fn expect_security() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("expect_security", &b"payload"[..])
+        .unwrap();
+
+    let security = key.security().unwrap();
+    key.expect_security(|label| label == security).unwrap();
+}
+
+#[test]
+fn expect_security_rejected() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("expect_security_rejected", &b"payload"[..])
+        .unwrap();
+
+    let err = key
+        .expect_security(|label| label == "expect_security_rejected_t")
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EKEYREJECTED));
+}
+
+#[test]
+fn parse_description() {
+    let desc = Description::parse("user;1000;100;3f010000;name").unwrap();
+    assert_eq!(desc.type_, "user");
+    assert_eq!(desc.uid, 1000);
+    assert_eq!(desc.gid, 100);
+    assert_eq!(desc.perms, Permission::from_raw(0x3f01_0000));
+    assert_eq!(desc.description, "name");
+}
+
+#[test]
+fn parse_description_extra_fields() {
+    let raw = "newer;new;user;1000;100;3f010000;name";
+    let desc = Description::parse(raw).unwrap();
+    assert_eq!(desc.type_, "user");
+    assert_eq!(desc.description, "name");
+    assert_eq!(desc.extra, ["newer", "new"]);
+    assert_eq!(desc.to_string(), raw);
+}
+
+#[test]
+fn parse_description_errors() {
+    assert_eq!(
+        Description::parse("1000;100;3f010000;name").unwrap_err(),
+        DescriptionParseError::TooFewFields,
+    );
+    assert_eq!(
+        Description::parse("user;root;100;3f010000;name").unwrap_err(),
+        DescriptionParseError::BadUid,
+    );
+    assert_eq!(
+        Description::parse("user;1000;-1;3f010000;name").unwrap_err(),
+        DescriptionParseError::BadGid,
+    );
+    assert_eq!(
+        Description::parse("user;1000;100;perms;name").unwrap_err(),
+        DescriptionParseError::BadPermissions,
+    );
+}
+
+#[test]
+fn description_categories() {
+    // possessor=all;user=view,read,search;group=view;other=
+    let desc = Description::parse("user;1000;100;3f0b0100;name").unwrap();
+
+    assert!(desc.can_read(Category::Possessor));
+    assert!(desc.can_write(Category::Possessor));
+    assert!(desc.can_set_attribute(Category::Possessor));
+
+    assert!(desc.can_view(Category::User));
+    assert!(desc.can_read(Category::User));
+    assert!(!desc.can_write(Category::User));
+    assert!(desc.can_search(Category::User));
+    assert!(!desc.can_link(Category::User));
+
+    assert!(desc.can_view(Category::Group));
+    assert!(!desc.can_read(Category::Group));
+
+    assert!(!desc.can_view(Category::Other));
+    assert!(!desc.can_search(Category::Other));
+
+    assert_eq!(Category::User.read(), Permission::USER_READ);
+    assert_eq!(Category::Other.all(), Permission::OTHER_ALL);
+}
+
+#[test]
+fn display_description() {
+    let raw = "user;1000;100;3f010000;name";
+    let desc = Description::parse(raw).unwrap();
+    assert_eq!(desc.to_string(), raw);
+}
+
+#[test]
+fn display_description_round_trip() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("display_description_round_trip", &b"payload"[..])
+        .unwrap();
+
+    let desc = key.description().unwrap();
+    let reparsed = Description::parse(&desc.to_string()).unwrap();
+    assert_eq!(reparsed.type_, desc.type_);
+    assert_eq!(reparsed.uid, desc.uid);
+    assert_eq!(reparsed.gid, desc.gid);
+    assert_eq!(reparsed.perms, desc.perms);
+    assert_eq!(reparsed.description, desc.description);
+}
+
+#[test]
+fn describe_raw() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("describe_raw", &b"payload"[..])
+        .unwrap();
+
+    let raw = key.describe_raw().unwrap();
+    assert!(raw.starts_with("user;"));
+    assert!(raw.ends_with(";describe_raw"));
+    assert_eq!(raw, key.description().unwrap().to_string());
+
+    let raw = keyring.describe_raw().unwrap();
+    assert!(raw.starts_with("keyring;"));
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::{capabilities, Key, KeyctlHash};
+
+use super::utils;
+
+// The 1536-bit MODP group from RFC 3526.
+const PRIME: &str = "\
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B139B22514A08798E34\
+    04DDEF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6\
+    F406B7EDEE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A6916\
+    3FA8FD24CF5F83655D23DCA3AD961C62F356208552BB9ED529077096966D670C354E4ABC9804F1746C08CA237327\
+    FFFFFFFFFFFFFFFF";
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn dh_keys(name: &str) -> (Key, Key, Key) {
+    let mut keyring = utils::new_test_keyring();
+    let prime = keyring
+        .add_key::<User, _, _>(format!("{}_prime", name), from_hex(PRIME))
+        .unwrap();
+    let base = keyring
+        .add_key::<User, _, _>(format!("{}_base", name), &[2][..])
+        .unwrap();
+    let private = keyring
+        .add_key::<User, _, _>(format!("{}_private", name), &[0x42; 32][..])
+        .unwrap();
+    (private, prime, base)
+}
+
+#[test]
+fn compute_dh_into() {
+    let (private, prime, base) = dh_keys("compute_dh_into");
+    let res = Key::compute_dh(&private, &prime, &base);
+
+    if capabilities().unwrap().supports_dh_compute() {
+        let expected = res.unwrap();
+        assert_eq!(expected.len(), PRIME.len() / 2);
+
+        // Too small to hold the result.
+        let mut buffer = Vec::with_capacity(4);
+        let sz = Key::compute_dh_into(&private, &prime, &base, &mut buffer).unwrap();
+        assert_eq!(sz, expected.len());
+        assert_eq!(buffer, expected);
+
+        // Reuse the allocation.
+        let sz = Key::compute_dh_into(&private, &prime, &base, &mut buffer).unwrap();
+        assert_eq!(sz, expected.len());
+        assert_eq!(buffer, expected);
+    } else {
+        assert_eq!(res.unwrap_err(), errno::Errno(libc::EOPNOTSUPP));
+    }
+}
+
+#[test]
+fn compute_dh_kdf() {
+    let (private, prime, base) = dh_keys("compute_dh_kdf");
+    let res = Key::compute_dh_kdf(
+        &private,
+        &prime,
+        &base,
+        KeyctlHash::Sha256,
+        Some(&b"other"[..]),
+        48,
+    );
+
+    if capabilities().unwrap().supports_dh_compute() {
+        assert_eq!(res.unwrap().len(), 48);
+    } else {
+        assert_eq!(res.unwrap_err(), errno::Errno(libc::EOPNOTSUPP));
+    }
+}
+
+#[test]
+fn compute_dh_kdf_empty_hash() {
+    let (private, prime, base) = dh_keys("compute_dh_kdf_empty_hash");
+    let err = Key::compute_dh_kdf(
+        &private,
+        &prime,
+        &base,
+        KeyctlHash::OtherEncoding("".into()),
+        None::<&[u8]>,
+        32,
+    )
+    .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
@@ -0,0 +1,206 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::{BTreeSet, HashSet};
+use std::convert::TryFrom;
+use std::thread;
+
+use crate::keytypes::User;
+use crate::{proc, Key, Keyring, SpecialKeyring};
+
+use super::utils;
+
+#[test]
+fn same_serial() {
+    let keyring = utils::new_test_keyring();
+    let key = utils::keyring_as_key(&keyring);
+
+    assert!(key.refers_to(&keyring));
+    assert!(key.same_key(&utils::keyring_as_key(&keyring)));
+}
+
+#[test]
+fn different_serial() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("different_serial", payload)
+        .unwrap();
+    let other = keyring
+        .add_key::<User, _, _>("different_serial_other", payload)
+        .unwrap();
+
+    assert!(!key.refers_to(&keyring));
+    assert!(!key.same_key(&other));
+}
+
+#[test]
+fn same_key_after_update() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("same_key_after_update", payload)
+        .unwrap();
+    let updated = keyring
+        .add_key::<User, _, _>("same_key_after_update", &b"updated"[..])
+        .unwrap();
+
+    assert!(key.same_key(&updated));
+}
+
+#[test]
+fn key_from_serial() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("key_from_serial", &b"payload"[..])
+        .unwrap();
+
+    let found = Key::from_serial(key.serial().get()).unwrap();
+    assert_eq!(found, key);
+
+    key.invalidate().unwrap();
+    utils::wait_for_key_gc(&found);
+    let err = Key::from_serial(found.serial().get()).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn keyring_from_serial() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("keyring_from_serial", &b"payload"[..])
+        .unwrap();
+
+    let found = Keyring::from_serial(keyring.serial().get()).unwrap();
+    assert_eq!(found, *keyring);
+
+    let err = Keyring::from_serial(key.serial().get()).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOTDIR));
+}
+
+#[test]
+fn from_non_positive_serial() {
+    let invalid = errno::Errno(libc::EINVAL);
+    assert_eq!(Key::from_serial(0).unwrap_err(), invalid);
+    assert_eq!(Key::from_serial(-3).unwrap_err(), invalid);
+    assert_eq!(Keyring::from_serial(0).unwrap_err(), invalid);
+    assert_eq!(Keyring::from_serial(-3).unwrap_err(), invalid);
+}
+
+#[test]
+fn serial_matches_proc_keys() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("serial_matches_proc_keys", &b"payload"[..])
+        .unwrap();
+
+    let entry = proc::key(key.serial()).unwrap();
+    assert_eq!(entry.serial, key.serial());
+    assert!(entry.description.starts_with("serial_matches_proc_keys"));
+
+    let entry = proc::key(keyring.serial()).unwrap();
+    assert_eq!(entry.serial, keyring.serial());
+}
+
+#[test]
+fn ordered_by_serial() {
+    let mut keyring = utils::new_test_keyring();
+    let first = keyring
+        .add_key::<User, _, _>("ordered_by_serial_first", &b"payload"[..])
+        .unwrap();
+    let second = keyring
+        .add_key::<User, _, _>("ordered_by_serial_second", &b"payload"[..])
+        .unwrap();
+
+    assert_eq!(first.cmp(&second), first.serial().cmp(&second.serial()));
+
+    let keys = vec![second.clone(), first.clone(), second.clone()]
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+    assert_eq!(keys.len(), 2);
+    assert!(keys.iter().zip(keys.iter().skip(1)).all(|(a, b)| a < b));
+
+    let keyrings = vec![(*keyring).clone(), (*keyring).clone()]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    assert_eq!(keyrings.len(), 1);
+}
+
+#[test]
+fn special_keyring_handle() {
+    let special = Keyring::special(SpecialKeyring::Thread);
+    assert_eq!(special.serial(), SpecialKeyring::Thread.serial());
+
+    let attached = Keyring::attach_or_create(SpecialKeyring::Thread).unwrap();
+    assert_ne!(special, attached);
+    assert_eq!(
+        special.describe_raw().unwrap(),
+        attached.describe_raw().unwrap(),
+    );
+}
+
+#[test]
+fn special_keyring_handle_other_thread() {
+    let mut special = Keyring::special(SpecialKeyring::Thread);
+    let attached = Keyring::attach_or_create(SpecialKeyring::Thread).unwrap();
+    let attached_serial = attached.serial();
+
+    let (other_serial, key_serial, other_children, attached_err) = thread::spawn(move || {
+        let other = Keyring::attach_or_create(SpecialKeyring::Thread).unwrap();
+        // The special handle resolves to this thread's keyring.
+        let key = special
+            .add_key::<User, _, _>("special_keyring_handle_other_thread", &b"payload"[..])
+            .unwrap();
+        let other_children = other.read_serials().unwrap();
+        // The attached handle still refers to the original thread's keyring, which this thread
+        // does not possess.
+        let attached_err = attached.read().unwrap_err();
+        (other.serial(), key.serial(), other_children, attached_err)
+    })
+    .join()
+    .unwrap();
+
+    assert_ne!(other_serial, attached_serial);
+    assert_eq!(other_children, [key_serial]);
+    assert_eq!(attached_err, errno::Errno(libc::EACCES));
+}
+
+#[test]
+fn key_keyring_conversion() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("key_keyring_conversion", &b"payload"[..])
+        .unwrap();
+
+    let as_key = Key::from((*keyring).clone());
+    assert!(as_key.refers_to(&keyring));
+    assert_eq!(as_key.as_keyring().unwrap().as_ref(), Some(&*keyring));
+    assert_eq!(Keyring::try_from(as_key).unwrap(), *keyring);
+
+    assert_eq!(key.as_keyring().unwrap(), None);
+    let err = Keyring::try_from(key).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOTDIR));
+}
@@ -26,8 +26,10 @@
 
 use std::time::Duration;
 
+use crate::api::with_zeroized;
 use crate::keytypes::User;
-use crate::KeyManager;
+use crate::upcall::RequestContext;
+use crate::{KeyManager, SpecialKeyring};
 
 use super::utils;
 
@@ -210,3 +212,163 @@ fn negate_unlinked_key() {
     let err = manager.negate(None, duration).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EPERM));
 }
+
+#[test]
+fn instantiate_from_already_instantiated() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let source = keyring
+        .add_key::<User, _, _>("instantiate_from_already_instantiated_source", payload)
+        .unwrap();
+    let key = keyring
+        .add_key::<User, _, _>("instantiate_from_already_instantiated", payload)
+        .unwrap();
+    let manager = KeyManager::test_new(key);
+
+    let err = manager.instantiate_from(&source, None).unwrap_err();
+    // The kernel doesn't have an authorization key for us to use.
+    assert_eq!(err, errno::Errno(libc::EPERM));
+}
+
+// Successfully instantiating a key requires the authorization key which only exists while the
+// kernel runs a `request-key` handler for it. The kernel runs the handler itself, outside of the
+// test process, so only the failure paths of `instantiate_from` can be tested here. Its payload
+// handling is tested through `with_zeroized` instead.
+
+#[test]
+fn instantiate_from_zeroizes_payload() {
+    let mut payload = b"secret payload".to_vec();
+    let res = with_zeroized(&mut payload, |payload| {
+        assert_eq!(payload, b"secret payload");
+        Err::<(), _>(errno::Errno(libc::EPERM))
+    });
+    assert_eq!(res, Err(errno::Errno(libc::EPERM)));
+    assert_eq!(payload.len(), 14);
+    assert!(payload.iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn instantiate_from_revoked_source() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let source = keyring
+        .add_key::<User, _, _>("instantiate_from_revoked_source_source", payload)
+        .unwrap();
+    let key = keyring
+        .add_key::<User, _, _>("instantiate_from_revoked_source", payload)
+        .unwrap();
+    source.clone().revoke().unwrap();
+    let manager = KeyManager::test_new(key);
+
+    // The source is read before instantiating.
+    let err = manager.instantiate_from(&source, None).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
+}
+
+#[test]
+fn instantiate_iov_already_instantiated() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("instantiate_iov_already_instantiated", payload)
+        .unwrap();
+    let manager = KeyManager::test_new(key);
+
+    let err = manager
+        .instantiate_iov(None, &[&b"pay"[..], &b"load"[..]])
+        .unwrap_err();
+    // The kernel doesn't have an authorization key for us to use.
+    assert_eq!(err, errno::Errno(libc::EPERM));
+}
+
+#[test]
+fn instantiate_iov_too_many_buffers() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("instantiate_iov_too_many_buffers", payload)
+        .unwrap();
+    let manager = KeyManager::test_new(key);
+
+    let payloads = vec![&b"a"[..]; libc::UIO_MAXIOV as usize + 1];
+    let err = manager.instantiate_iov(None, &payloads).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn reject_into_special_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("reject_into_special_keyring", payload)
+        .unwrap();
+    let manager = KeyManager::test_new(key);
+
+    let duration = Duration::from_secs(1);
+    let errno = errno::Errno(libc::EKEYREJECTED);
+    let err = manager
+        .reject(SpecialKeyring::Session, duration, errno)
+        .unwrap_err();
+    // The kernel doesn't have an authorization key for us to use.
+    assert_eq!(err, errno::Errno(libc::EPERM));
+}
+
+#[test]
+fn negate_into_special_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("negate_into_special_keyring", payload)
+        .unwrap();
+    let manager = KeyManager::test_new(key);
+
+    let duration = Duration::from_secs(1);
+    let err = manager
+        .negate(SpecialKeyring::Session, duration)
+        .unwrap_err();
+    // The kernel doesn't have an authorization key for us to use.
+    assert_eq!(err, errno::Errno(libc::EPERM));
+}
+
+#[test]
+fn fulfill_without_authority() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("fulfill_without_authority", payload)
+        .unwrap();
+
+    let mut called = false;
+    let err = key
+        .fulfill(None, || {
+            called = true;
+            Ok(b"new payload".to_vec())
+        })
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert!(!called);
+    assert_eq!(key.read().unwrap(), payload);
+}
+
+#[test]
+fn assume_authority_without_authorization_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("assume_authority_without_authorization_key", payload)
+        .unwrap();
+    let args = [
+        "create".into(),
+        key.serial().to_string(),
+        "0".into(),
+        "0".into(),
+        "0".into(),
+        "0".into(),
+        "0".into(),
+    ];
+    let mut context = RequestContext::from_args(&args).unwrap();
+
+    let err = context.assume_authority().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert_eq!(context.callout_info, None);
+}
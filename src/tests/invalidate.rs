@@ -25,6 +25,7 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::keytypes::User;
+use crate::Permission;
 
 use super::utils;
 use super::utils::kernel::*;
@@ -122,3 +123,41 @@ fn invalidate_keyring() {
     let err = key.description().unwrap_err();
     assert_eq!(err, errno::Errno(libc::ENOKEY));
 }
+
+#[test]
+fn destroy_keyring() {
+    let mut keyring = utils::new_test_keyring_manual();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("destroy_keyring", payload)
+        .unwrap();
+    let keyring_observer = keyring.clone();
+
+    keyring.destroy().unwrap();
+    utils::wait_for_keyring_gc(&keyring_observer);
+
+    let err = keyring_observer.read().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+
+    utils::wait_for_key_gc(&key);
+
+    let err = key.description().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn destroy_keyring_without_write() {
+    let mut keyring = utils::new_test_keyring_manual();
+    keyring
+        .set_permissions(Permission::POSSESSOR_ALL - Permission::POSSESSOR_WRITE)
+        .unwrap();
+    let keyring_observer = keyring.clone();
+
+    // Clearing fails, but the keyring is still invalidated.
+    let err = keyring.destroy().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EACCES));
+    utils::wait_for_keyring_gc(&keyring_observer);
+
+    let err = keyring_observer.description().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
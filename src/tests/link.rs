@@ -297,3 +297,70 @@ fn multiply_link_keyring_into_keyring() {
     assert_eq!(keyrings[0], new_keyring);
     assert_eq!(keyrings[1], inner_keyring);
 }
+
+#[test]
+fn link_key_verified() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("link_key_verified", payload)
+        .unwrap();
+    let mut target_keyring = keyring.add_keyring("link_key_verified_target").unwrap();
+
+    assert!(!target_keyring.contains(&key).unwrap());
+    target_keyring.link_key_verified(&key).unwrap();
+    assert!(target_keyring.contains(&key).unwrap());
+}
+
+#[test]
+fn link_key_verified_vanished() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("link_key_verified_vanished", payload)
+        .unwrap();
+    let mut target_keyring = keyring
+        .add_keyring("link_key_verified_vanished_target")
+        .unwrap();
+
+    // Simulate a concurrent clear between linking and verification.
+    target_keyring.link_key(&key).unwrap();
+    target_keyring.clear().unwrap();
+
+    let err = target_keyring.verify_link(&key).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOLINK));
+}
+
+#[test]
+fn try_link_key_collision() {
+    let mut keyring = utils::new_test_keyring();
+    let mut other = keyring.add_keyring("try_link_key_collision_other").unwrap();
+    let existing = keyring
+        .add_key::<User, _, _>("try_link_key_collision", &b"existing"[..])
+        .unwrap();
+    let key = other
+        .add_key::<User, _, _>("try_link_key_collision", &b"new"[..])
+        .unwrap();
+
+    let err = keyring.try_link_key(&key).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EEXIST));
+
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [existing]);
+}
+
+#[test]
+fn try_link_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut other = keyring.add_keyring("try_link_key_other").unwrap();
+    let key = other
+        .add_key::<User, _, _>("try_link_key", &b"payload"[..])
+        .unwrap();
+
+    keyring.try_link_key(&key).unwrap();
+    // Linking an already-linked key is fine.
+    keyring.try_link_key(&key).unwrap();
+
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [key]);
+}
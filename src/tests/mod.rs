@@ -29,17 +29,26 @@
 pub(crate) mod utils;
 
 mod add;
+mod capabilities;
 mod clear;
+mod default;
 mod describe;
+mod dh;
+mod identity;
 mod instantiate;
 mod invalidate;
 mod keytype;
 mod link;
+mod moving;
 mod newring;
 mod permitting;
+mod pkey;
+mod proc;
 mod reading;
+mod restrict;
 mod revoke;
 mod search;
 mod timeout;
 mod unlink;
 mod update;
+mod watch;
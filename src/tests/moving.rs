@@ -0,0 +1,151 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+
+use super::utils;
+
+#[test]
+fn move_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_key_from").unwrap();
+    let mut to = keyring.add_keyring("move_key_to").unwrap();
+    let key = from
+        .add_key::<User, _, _>("move_key", &b"payload"[..])
+        .unwrap();
+
+    from.move_key(&key, &mut to, false).unwrap();
+
+    let (keys, _) = from.read().unwrap();
+    assert!(keys.is_empty());
+    let (keys, _) = to.read().unwrap();
+    assert_eq!(keys, [key]);
+}
+
+#[test]
+fn move_key_exclusive() {
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_key_exclusive_from").unwrap();
+    let mut to = keyring.add_keyring("move_key_exclusive_to").unwrap();
+    let key = from
+        .add_key::<User, _, _>("move_key_exclusive", &b"payload"[..])
+        .unwrap();
+    let existing = to
+        .add_key::<User, _, _>("move_key_exclusive", &b"existing"[..])
+        .unwrap();
+
+    let err = from.move_key(&key, &mut to, false).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EEXIST));
+
+    let (keys, _) = from.read().unwrap();
+    assert_eq!(keys, [key]);
+    let (keys, _) = to.read().unwrap();
+    assert_eq!(keys, [existing]);
+}
+
+#[test]
+fn move_key_replace() {
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_key_replace_from").unwrap();
+    let mut to = keyring.add_keyring("move_key_replace_to").unwrap();
+    let key = from
+        .add_key::<User, _, _>("move_key_replace", &b"payload"[..])
+        .unwrap();
+    to.add_key::<User, _, _>("move_key_replace", &b"existing"[..])
+        .unwrap();
+
+    from.move_key(&key, &mut to, true).unwrap();
+
+    let (keys, _) = from.read().unwrap();
+    assert!(keys.is_empty());
+    let (keys, _) = to.read().unwrap();
+    assert_eq!(keys, [key]);
+}
+
+#[test]
+fn move_key_not_linked() {
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_key_not_linked_from").unwrap();
+    let mut to = keyring.add_keyring("move_key_not_linked_to").unwrap();
+    let key = keyring
+        .add_key::<User, _, _>("move_key_not_linked", &b"payload"[..])
+        .unwrap();
+
+    let err = from.move_key(&key, &mut to, false).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOENT));
+}
+
+#[test]
+fn move_all() {
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_all_from").unwrap();
+    let mut to = keyring.add_keyring("move_all_to").unwrap();
+    let first = from
+        .add_key::<User, _, _>("move_all_first", &b"payload"[..])
+        .unwrap();
+    let second = from
+        .add_key::<User, _, _>("move_all_second", &b"payload"[..])
+        .unwrap();
+    let nested = from.add_keyring("move_all_nested").unwrap();
+
+    assert_eq!(from.move_all(&mut to, false).unwrap(), 3);
+
+    assert!(from.is_empty().unwrap());
+    let (keys, keyrings) = to.read().unwrap();
+    assert_eq!(keys, [first, second]);
+    assert_eq!(keyrings, [nested]);
+}
+
+#[test]
+fn move_all_exclusive() {
+    let mut keyring = utils::new_test_keyring();
+    let mut from = keyring.add_keyring("move_all_exclusive_from").unwrap();
+    let mut to = keyring.add_keyring("move_all_exclusive_to").unwrap();
+    let colliding = from
+        .add_key::<User, _, _>("move_all_exclusive", &b"payload"[..])
+        .unwrap();
+    let other = from
+        .add_key::<User, _, _>("move_all_exclusive_other", &b"payload"[..])
+        .unwrap();
+    let existing = to
+        .add_key::<User, _, _>("move_all_exclusive", &b"existing"[..])
+        .unwrap();
+
+    assert_eq!(from.move_all(&mut to, false).unwrap(), 1);
+
+    let (keys, _) = from.read().unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0], colliding);
+    let (keys, _) = to.read().unwrap();
+    assert_eq!(keys, [existing, other]);
+
+    assert_eq!(from.move_all(&mut to, true).unwrap(), 1);
+
+    assert!(from.is_empty().unwrap());
+    let (keys, _) = to.read().unwrap();
+    assert_eq!(keys.len(), 2);
+    assert!(keys.contains(&colliding));
+}
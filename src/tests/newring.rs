@@ -27,6 +27,7 @@
 use std::iter;
 
 use crate::keytypes::User;
+use crate::{Keyring, SpecialKeyring};
 
 use super::utils;
 use super::utils::kernel::*;
@@ -72,6 +73,19 @@ fn empty_keyring_description() {
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
+#[test]
+fn nul_keyring_description() {
+    let mut keyring = utils::new_test_keyring();
+    let err = keyring.add_keyring("nul\0keyring").unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn nul_session_name() {
+    let err = Keyring::join_session("nul\0session").unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
 #[test]
 fn max_keyring_description() {
     let mut keyring = utils::new_test_keyring();
@@ -130,3 +144,40 @@ fn duplicate_keyring_names() {
     let err = new_keyring1.description().unwrap_err();
     assert_eq!(err, errno::Errno(libc::ENOKEY));
 }
+
+#[test]
+fn attach_persistent_for_self() {
+    let mut keyring = utils::new_test_keyring();
+    let persistent = match keyring.attach_persistent() {
+        Ok(persistent) => persistent,
+        Err(errno::Errno(libc::EOPNOTSUPP)) => {
+            eprintln!("This kernel does not support persistent keyrings; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to attach the persistent keyring: {}", err),
+    };
+
+    let uid = unsafe { libc::getuid() };
+    let persistent_for = keyring.attach_persistent_for(uid).unwrap();
+    assert_eq!(persistent_for, persistent);
+
+    keyring.unlink_keyring(&persistent).unwrap();
+}
+
+#[test]
+fn create_detached() {
+    let session = Keyring::attach_or_create(SpecialKeyring::Session).unwrap();
+    let mut keyring = utils::new_test_keyring();
+    let detached = keyring.create_detached("create_detached").unwrap();
+
+    assert_eq!(
+        Keyring::attach_or_create(SpecialKeyring::Session).unwrap(),
+        session,
+    );
+    assert_ne!(detached, session);
+    assert_eq!(
+        detached.description().unwrap().description,
+        "create_detached"
+    );
+    assert_eq!(keyring.read().unwrap().1, [detached]);
+}
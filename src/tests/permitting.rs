@@ -268,3 +268,112 @@ fn remove_setattr() {
     let err = key.set_permissions(Permission::all()).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EACCES));
 }
+
+#[test]
+fn revalidate_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("revalidate_key", payload)
+        .unwrap();
+
+    key.revalidate().unwrap();
+}
+
+#[test]
+fn revalidate_lost_access() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("revalidate_lost_access", payload)
+        .unwrap();
+
+    // The owner only gets the user permissions, so this simulates losing group membership.
+    let perms = Permission::GROUP_ALL | Permission::USER_SET_ATTRIBUTE;
+    key.set_permissions(perms).unwrap();
+
+    let err = key.revalidate().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EACCES));
+}
+
+#[test]
+fn revalidate_gone() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("revalidate_gone", payload)
+        .unwrap();
+
+    keyring.unlink_key(&key).unwrap();
+    utils::wait_for_key_gc(&key);
+
+    let err = key.revalidate().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn is_valid() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring.add_key::<User, _, _>("is_valid", payload).unwrap();
+    assert!(key.is_valid().unwrap());
+
+    let revoked = keyring
+        .add_key::<User, _, _>("is_valid_revoked", payload)
+        .unwrap();
+    let revoked_handle = revoked.clone();
+    revoked.revoke().unwrap();
+    assert!(!revoked_handle.is_valid().unwrap());
+
+    keyring.unlink_key(&key).unwrap();
+    utils::wait_for_key_gc(&key);
+    assert!(!key.is_valid().unwrap());
+}
+
+#[test]
+fn is_valid_lost_access() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("is_valid_lost_access", payload)
+        .unwrap();
+
+    let perms = Permission::GROUP_ALL | Permission::USER_SET_ATTRIBUTE;
+    key.set_permissions(perms).unwrap();
+
+    let err = key.is_valid().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EACCES));
+}
+
+#[test]
+fn grant_possessor() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("grant_possessor", payload)
+        .unwrap();
+
+    let perms = Permission::POSSESSOR_VIEW
+        | Permission::POSSESSOR_SEARCH
+        | Permission::POSSESSOR_SET_ATTRIBUTE
+        | Permission::USER_VIEW;
+    key.set_permissions(perms).unwrap();
+    key.grant_possessor(Permission::POSSESSOR_READ).unwrap();
+
+    let desc = key.description().unwrap();
+    assert_eq!(desc.perms, perms | Permission::POSSESSOR_READ);
+}
+
+#[test]
+fn grant_possessor_other_bits() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("grant_possessor_other_bits", payload)
+        .unwrap();
+
+    let err = key
+        .grant_possessor(Permission::POSSESSOR_READ | Permission::USER_READ)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use keyutils_raw::{
+    KEYCTL_SUPPORTS_DECRYPT, KEYCTL_SUPPORTS_ENCRYPT, KEYCTL_SUPPORTS_SIGN, KEYCTL_SUPPORTS_VERIFY,
+};
+
+use crate::keytypes::Asymmetric;
+use crate::{KeyctlEncoding, KeyctlHash};
+
+use super::utils;
+
+// A self-signed 2048-bit RSA certificate.
+const RSA_CERT: &[u8] = include_bytes!("data/rsa-2048.der");
+// The private key of the certificate in PKCS#8 format.
+const RSA_PRIVATE_KEY: &[u8] = include_bytes!("data/rsa-2048.pk8");
+// A PKCS#1 v1.5 signature of `DIGEST` as a SHA-256 digest made with the private key.
+const RSA_SHA256_SIG: &[u8] = include_bytes!("data/rsa-2048-sha256.sig");
+const DIGEST: &[u8] = &[0x42; 32];
+
+#[test]
+fn rsa_supported_algos() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<Asymmetric, _, _>("rsa_supported_algos", RSA_CERT)
+        .unwrap();
+
+    let support = key.pkey_supported_algos().unwrap();
+    assert!(support.supports(&KeyctlEncoding::RsassaPkcs1V15, &KeyctlHash::Sha256));
+    assert!(support.supports(&KeyctlEncoding::RsaesPkcs1V15, &KeyctlHash::Sha256));
+
+    let info = support
+        .info(&KeyctlEncoding::RsassaPkcs1V15, &KeyctlHash::Sha256)
+        .unwrap();
+    assert_eq!(info.key_size, 2048);
+}
+
+#[test]
+fn user_key_supported_algos() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<crate::keytypes::User, _, _>("user_key_supported_algos", payload)
+        .unwrap();
+
+    let err = key.pkey_supported_algos().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+}
+
+#[test]
+fn rsa_pkey_query() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<Asymmetric, _, _>("rsa_pkey_query", RSA_CERT)
+        .unwrap();
+
+    let info = key.pkey_query("enc=pkcs1 hash=sha256").unwrap();
+    assert_eq!(info.key_size, 2048);
+    assert_eq!(info.max_sig_size, 256);
+    assert_eq!(info.max_enc_size, 256);
+    // Only the public half of the key is available.
+    assert_ne!(info.supported_ops & KEYCTL_SUPPORTS_ENCRYPT, 0);
+    assert_ne!(info.supported_ops & KEYCTL_SUPPORTS_VERIFY, 0);
+    assert_eq!(info.supported_ops & KEYCTL_SUPPORTS_DECRYPT, 0);
+    assert_eq!(info.supported_ops & KEYCTL_SUPPORTS_SIGN, 0);
+}
+
+#[test]
+fn pkey_query_nul() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<Asymmetric, _, _>("pkey_query_nul", RSA_CERT)
+        .unwrap();
+
+    let err = key.pkey_query("enc=pkcs1\0").unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn rsa_pkey_verify() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<Asymmetric, _, _>("rsa_pkey_verify", RSA_CERT)
+        .unwrap();
+
+    assert!(key
+        .pkey_verify("enc=pkcs1 hash=sha256", DIGEST, RSA_SHA256_SIG)
+        .unwrap());
+
+    let mut bad_digest = DIGEST.to_vec();
+    bad_digest[0] ^= 0x01;
+    assert!(!key
+        .pkey_verify("enc=pkcs1 hash=sha256", &bad_digest, RSA_SHA256_SIG)
+        .unwrap());
+}
+
+#[test]
+fn rsa_pkey_sign() {
+    let mut keyring = utils::new_test_keyring();
+    let private = match keyring.add_key::<Asymmetric, _, _>("rsa_pkey_sign", RSA_PRIVATE_KEY) {
+        Ok(key) => key,
+        Err(errno::Errno(libc::EBADMSG)) => {
+            eprintln!("This kernel does not support PKCS#8 private keys; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to add a private key: {}", err),
+    };
+
+    let signature = private.pkey_sign("enc=pkcs1 hash=sha256", DIGEST).unwrap();
+    // PKCS#1 v1.5 signatures are deterministic.
+    assert_eq!(signature, RSA_SHA256_SIG);
+}
+
+#[test]
+fn pkey_verify_unsupported() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<crate::keytypes::User, _, _>("pkey_verify_unsupported", payload)
+        .unwrap();
+
+    let err = key
+        .pkey_verify("enc=pkcs1 hash=sha256", DIGEST, RSA_SHA256_SIG)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+}
+
+#[test]
+fn rsa_pkey_encrypt_decrypt() {
+    let mut keyring = utils::new_test_keyring();
+    let public = keyring
+        .add_key::<Asymmetric, _, _>("rsa_pkey_encrypt", RSA_CERT)
+        .unwrap();
+
+    let plaintext = &b"plaintext"[..];
+    let ciphertext = public.pkey_encrypt("enc=pkcs1", plaintext).unwrap();
+    assert_eq!(ciphertext.len(), 256);
+    assert_ne!(ciphertext, plaintext);
+
+    let private = match keyring.add_key::<Asymmetric, _, _>("rsa_pkey_decrypt", RSA_PRIVATE_KEY) {
+        Ok(key) => key,
+        Err(errno::Errno(libc::EBADMSG)) => {
+            eprintln!("This kernel does not support PKCS#8 private keys; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to add a private key: {}", err),
+    };
+
+    let decrypted = private.pkey_decrypt("enc=pkcs1", &ciphertext).unwrap();
+    assert_eq!(decrypted, plaintext);
+}
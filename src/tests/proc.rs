@@ -0,0 +1,90 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::proc;
+
+use super::utils;
+
+#[test]
+fn reachable_key_is_not_orphaned() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("reachable_key_is_not_orphaned", payload)
+        .unwrap();
+
+    let orphans = proc::orphaned_keys().unwrap();
+    assert!(!orphans.iter().any(|orphan| orphan.serial == key.serial()));
+}
+
+#[test]
+fn orphaned_key() {
+    let mut keyring = utils::new_test_keyring();
+    // The persistent keyring is kept alive by the kernel even when it is not linked anywhere.
+    let mut persistent = match keyring.attach_persistent() {
+        Ok(persistent) => persistent,
+        Err(errno::Errno(libc::EOPNOTSUPP)) => {
+            eprintln!("This kernel does not support persistent keyrings; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to attach the persistent keyring: {}", err),
+    };
+    let payload = &b"payload"[..];
+    let key = persistent
+        .add_key::<User, _, _>("orphaned_key", payload)
+        .unwrap();
+    keyring.unlink_keyring(&persistent).unwrap();
+
+    let orphans = proc::orphaned_keys().unwrap();
+    assert!(orphans.iter().any(|orphan| orphan.serial == key.serial()));
+
+    // Clean up the key from the persistent keyring.
+    let mut persistent = keyring.attach_persistent().unwrap();
+    persistent.unlink_key(&key).unwrap();
+    keyring.unlink_keyring(&persistent).unwrap();
+}
+
+#[test]
+fn proc_members() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let first = keyring
+        .add_key::<User, _, _>("proc_members_first", payload)
+        .unwrap();
+    let second = keyring
+        .add_key::<User, _, _>("proc_members_second", payload)
+        .unwrap();
+
+    let members = keyring.proc_members().unwrap();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].serial, first.serial());
+    assert!(members[0].description.starts_with("proc_members_first"));
+    assert_eq!(members[1].serial, second.serial());
+    assert!(members[1].description.starts_with("proc_members_second"));
+    assert!(members.iter().all(|member| member.usage > 0));
+    assert!(members.iter().all(|member| member.type_ == "user"));
+}
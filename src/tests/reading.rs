@@ -24,8 +24,8 @@
 // (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::keytypes::User;
-use crate::Permission;
+use crate::keytypes::{self, logon, trusted, Logon, User};
+use crate::{Key, KeyringNode, Permission};
 
 use super::utils;
 
@@ -187,3 +187,446 @@ fn reinstated_read_perm() {
     let actual_payload = key.read().unwrap();
     assert_eq!(payload, actual_payload.as_slice());
 }
+
+#[test]
+fn read_cstring() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload\0"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_cstring", payload)
+        .unwrap();
+
+    let cstring = key.read_cstring().unwrap();
+    assert_eq!(cstring.as_bytes(), b"payload");
+}
+
+#[test]
+fn read_cstring_unterminated() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_cstring_unterminated", payload)
+        .unwrap();
+
+    let err = key.read_cstring().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn read_cstring_interior_nul() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"pay\0load\0"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_cstring_interior_nul", payload)
+        .unwrap();
+
+    let err = key.read_cstring().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn type_histogram() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    keyring
+        .add_key::<User, _, _>("type_histogram_a", payload)
+        .unwrap();
+    keyring
+        .add_key::<User, _, _>("type_histogram_b", payload)
+        .unwrap();
+    keyring.add_keyring("type_histogram_keyring").unwrap();
+
+    let histogram = keyring.type_histogram().unwrap();
+    assert_eq!(histogram.len(), 2);
+    assert_eq!(histogram["user"], 2);
+    assert_eq!(histogram["keyring"], 1);
+}
+
+#[test]
+fn print_text() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload text"[..];
+    let key = keyring
+        .add_key::<User, _, _>("print_text", payload)
+        .unwrap();
+
+    assert_eq!(key.print().unwrap(), "payload text");
+}
+
+#[test]
+fn print_binary() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"\xde\xad\nbeef"[..];
+    let key = keyring
+        .add_key::<User, _, _>("print_binary", payload)
+        .unwrap();
+
+    assert_eq!(key.print().unwrap(), ":hex:dead0a62656566");
+}
+
+fn tlv_record(type_: u8, value: &[u8]) -> Vec<u8> {
+    let mut record = vec![type_];
+    record.extend_from_slice(&(value.len() as u32).to_ne_bytes());
+    record.extend_from_slice(value);
+    record
+}
+
+#[test]
+fn read_tlv() {
+    let mut keyring = utils::new_test_keyring();
+    let mut payload = tlv_record(1, b"first");
+    payload.extend(tlv_record(2, b""));
+    payload.extend(tlv_record(3, b"\x00\xff"));
+    let key = keyring.add_key::<User, _, _>("read_tlv", payload).unwrap();
+
+    let records = key.read_tlv().unwrap();
+    assert_eq!(
+        records,
+        vec![
+            (1, b"first".to_vec()),
+            (2, Vec::new()),
+            (3, b"\x00\xff".to_vec()),
+        ],
+    );
+}
+
+#[test]
+fn read_tlv_truncated() {
+    let mut keyring = utils::new_test_keyring();
+    let mut payload = tlv_record(1, b"first");
+    payload.pop();
+    let key = keyring
+        .add_key::<User, _, _>("read_tlv_truncated", payload)
+        .unwrap();
+
+    let err = key.read_tlv().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EBADMSG));
+}
+
+#[test]
+fn read_tlv_truncated_length() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"\x01\x00\x00"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_tlv_truncated_length", payload)
+        .unwrap();
+
+    let err = key.read_tlv().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EBADMSG));
+}
+
+#[test]
+fn read_into_locked() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_into_locked", payload)
+        .unwrap();
+
+    let mut buffer = [0; 32];
+    let len = key.read_into_locked(&mut buffer).unwrap();
+    assert_eq!(len, payload.len());
+    assert_eq!(&buffer[..len], payload);
+}
+
+#[test]
+fn read_into_locked_short() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_into_locked_short", payload)
+        .unwrap();
+
+    let mut buffer = [0; 3];
+    let len = key.read_into_locked(&mut buffer).unwrap();
+    assert_eq!(len, payload.len());
+}
+
+#[test]
+fn debug_omits_payload() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("debug_omits_payload", &b"secret"[..])
+        .unwrap();
+
+    let debug = format!("{:?}", key);
+    assert!(debug.contains(&key.serial().get().to_string()));
+    assert!(!debug.contains("secret"));
+}
+
+#[test]
+fn read_only_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_only_key", payload)
+        .unwrap();
+
+    let read_only = key.read_only();
+    assert_eq!(read_only.serial(), key.serial());
+    assert_eq!(
+        read_only.description().unwrap().description,
+        "read_only_key",
+    );
+    assert_eq!(read_only.security().unwrap(), key.security().unwrap());
+    assert_eq!(read_only.read().unwrap(), payload);
+    assert_eq!(read_only.payload_len().unwrap(), payload.len());
+}
+
+#[test]
+fn read_logon_key() {
+    let mut keyring = utils::new_test_keyring();
+    let description = logon::Description::new("read_logon_key", "secret").unwrap();
+    let key = keyring
+        .add_key::<Logon, _, _>(description, &b"payload"[..])
+        .unwrap();
+
+    let err = key.read().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+}
+
+#[test]
+fn read_into_reuses_buffer() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("read_into_reuses_buffer", &b"a longer payload"[..])
+        .unwrap();
+
+    let mut buffer = Vec::new();
+    let len = key.read_into(&mut buffer).unwrap();
+    assert_eq!(len, 16);
+    assert_eq!(buffer, b"a longer payload");
+    let capacity = buffer.capacity();
+
+    key.update::<User, _>(&b"short"[..]).unwrap();
+    let len = key.read_into(&mut buffer).unwrap();
+    assert_eq!(len, 5);
+    assert_eq!(buffer, b"short");
+    assert_eq!(buffer.capacity(), capacity);
+}
+
+#[test]
+fn read_into_after_growing() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("read_into_after_growing", &b"short"[..])
+        .unwrap();
+
+    let mut buffer = Vec::new();
+    let len = key.read_into(&mut buffer).unwrap();
+    assert_eq!(len, 5);
+
+    let large = vec![b'l'; 4096];
+    key.update::<User, _>(&large[..]).unwrap();
+    let len = key.read_into(&mut buffer).unwrap();
+    assert_eq!(len, large.len());
+    assert_eq!(buffer, large);
+}
+
+#[test]
+fn iter_keyring_tree() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("iter_keyring_tree_key", &b"payload"[..])
+        .unwrap();
+    let mut child = keyring.add_keyring("iter_keyring_tree_child").unwrap();
+    let nested = child
+        .add_key::<User, _, _>("iter_keyring_tree_nested", &b"payload"[..])
+        .unwrap();
+
+    let nodes = keyring.iter().unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        nodes,
+        [
+            (KeyringNode::Key(key), 0),
+            (KeyringNode::Keyring(child), 0),
+            (KeyringNode::Key(nested), 1),
+        ],
+    );
+}
+
+#[test]
+fn iter_keyring_tree_shared() {
+    let mut keyring = utils::new_test_keyring();
+    let mut first = keyring.add_keyring("iter_keyring_tree_shared_a").unwrap();
+    let mut second = keyring.add_keyring("iter_keyring_tree_shared_b").unwrap();
+    let shared = first.add_keyring("iter_keyring_tree_shared").unwrap();
+    second.link_keyring(&shared).unwrap();
+    let key = {
+        let mut shared = shared.clone();
+        shared
+            .add_key::<User, _, _>("iter_keyring_tree_shared_key", &b"payload"[..])
+            .unwrap()
+    };
+
+    // The shared keyring is only descended into once.
+    let nodes = keyring.iter().unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        nodes,
+        [
+            (KeyringNode::Keyring(first), 0),
+            (KeyringNode::Keyring(shared.clone()), 1),
+            (KeyringNode::Key(key), 2),
+            (KeyringNode::Keyring(second), 0),
+            (KeyringNode::Keyring(shared), 1),
+        ],
+    );
+}
+
+#[test]
+fn iter_keyring_tree_unreadable() {
+    let mut keyring = utils::new_test_keyring();
+    let mut child = keyring.add_keyring("iter_keyring_tree_unreadable").unwrap();
+    child
+        .add_key::<User, _, _>("iter_keyring_tree_unreadable_key", &b"payload"[..])
+        .unwrap();
+    child
+        .set_permissions(Permission::POSSESSOR_VIEW | Permission::USER_VIEW)
+        .unwrap();
+
+    let nodes = keyring.iter().unwrap().collect::<Vec<_>>();
+    assert_eq!(nodes, [(KeyringNode::Keyring(child), 0)]);
+}
+
+#[test]
+fn iter_keyring_tree_hidden_child() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("iter_keyring_tree_hidden_child_key", &b"payload"[..])
+        .unwrap();
+    let mut hidden = keyring
+        .add_key::<User, _, _>("iter_keyring_tree_hidden_child", &b"payload"[..])
+        .unwrap();
+    hidden
+        .set_permissions(Permission::POSSESSOR_SEARCH)
+        .unwrap();
+
+    // Only the child which may not be viewed is skipped.
+    let nodes = keyring.iter().unwrap().collect::<Vec<_>>();
+    assert_eq!(nodes, [(KeyringNode::Key(key), 0)]);
+}
+
+#[test]
+fn iter_keyring_tree_unreadable_root() {
+    let mut keyring = utils::new_test_keyring();
+    let mut root = keyring
+        .add_keyring("iter_keyring_tree_unreadable_root")
+        .unwrap();
+    root.set_permissions(Permission::POSSESSOR_VIEW | Permission::USER_VIEW)
+        .unwrap();
+
+    let err = root.iter().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EACCES));
+}
+
+#[test]
+fn iter_invalid_keyring() {
+    let keyring = utils::invalid_keyring();
+    let err = keyring.iter().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn read_trusted_blob() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("read_trusted_blob", &b"deadbeef"[..])
+        .unwrap();
+    assert_eq!(trusted::read_blob(&key).unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+
+    let key = keyring
+        .add_key::<User, _, _>("read_trusted_blob_not_hex", &b"not hex"[..])
+        .unwrap();
+    let err = trusted::read_blob(&key).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn read_string() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("read_string", "tøken".as_bytes())
+        .unwrap();
+    assert_eq!(key.read_string().unwrap(), "tøken");
+
+    let key = keyring
+        .add_key::<User, _, _>("read_string_invalid", &b"\xff\xfe"[..])
+        .unwrap();
+    let err = key.read_string().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EILSEQ));
+}
+
+#[test]
+fn read_secret() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"secret payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_secret", payload)
+        .unwrap();
+
+    let secret = key.read_secret().unwrap();
+    assert_eq!(&*secret, payload);
+    assert_eq!(secret.as_bytes(), payload);
+
+    let debug = format!("{:?}", secret);
+    assert!(debug.contains("redacted"));
+    assert!(!debug.contains("secret payload"));
+}
+
+#[test]
+fn read_as() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring.add_key::<User, _, _>("read_as", payload).unwrap();
+    assert_eq!(key.read_as::<User>().unwrap(), payload);
+
+    let as_key = Key::from_serial(keyring.serial().get()).unwrap();
+    assert_eq!(
+        as_key.read_as::<keytypes::Keyring>().unwrap(),
+        [key.serial()],
+    );
+}
+
+#[test]
+fn keyring_len() {
+    let mut keyring = utils::new_test_keyring();
+    assert_eq!(keyring.len().unwrap(), 0);
+    assert!(keyring.is_empty().unwrap());
+
+    let key = keyring
+        .add_key::<User, _, _>("keyring_len", &b"payload"[..])
+        .unwrap();
+    keyring.add_keyring("keyring_len_child").unwrap();
+    assert_eq!(keyring.len().unwrap(), 2);
+    assert!(!keyring.is_empty().unwrap());
+
+    let not_a_keyring = utils::key_as_keyring(&key);
+    let err = not_a_keyring.len().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOTDIR));
+}
+
+#[test]
+fn keyring_children() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("keyring_children", &b"payload"[..])
+        .unwrap();
+    let child = keyring.add_keyring("keyring_children_child").unwrap();
+
+    let children = keyring.children().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].serial(), key.serial());
+    assert_eq!(children[1].serial(), child.serial());
+
+    assert!(!children[0].is_keyring().unwrap());
+    assert!(children[1].is_keyring().unwrap());
+    assert_eq!(
+        children[1].description().unwrap().description,
+        "keyring_children_child",
+    );
+
+    let err = children[0].clone().into_keyring().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOTDIR));
+    assert_eq!(children[0].clone().into_key(), key);
+    assert_eq!(children[1].clone().into_keyring().unwrap(), child);
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::asymmetric::RestrictionBuilder;
+use crate::keytypes::{Asymmetric, User};
+use crate::Restriction;
+
+use super::utils;
+
+const RSA_CERT: &[u8] = include_bytes!("data/rsa-2048.der");
+
+#[test]
+fn restrict_all() {
+    let mut keyring = utils::new_test_keyring();
+    let mut restricted = keyring.add_keyring("restrict_all").unwrap();
+
+    restricted.restrict_all().unwrap();
+
+    let payload = &b"payload"[..];
+    let err = restricted
+        .add_key::<User, _, _>("restrict_all_key", payload)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EPERM));
+}
+
+#[test]
+fn restrict_signed_by_key() {
+    let mut keyring = utils::new_test_keyring();
+    let ca = keyring
+        .add_key::<Asymmetric, _, _>("restrict_signed_by_key_ca", RSA_CERT)
+        .unwrap();
+    let mut restricted = keyring.add_keyring("restrict_signed_by_key").unwrap();
+
+    restricted
+        .restrict(&RestrictionBuilder::signed_by_key(&ca).chain().build())
+        .unwrap();
+
+    let payload = &b"payload"[..];
+    let err = restricted
+        .add_key::<User, _, _>("restrict_signed_by_key_user", payload)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+}
+
+#[test]
+fn restrict_none() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("restrict_none_key", payload)
+        .unwrap();
+    let mut restricted = keyring.add_keyring("restrict_none").unwrap();
+
+    restricted.restrict(&Restriction::None).unwrap();
+
+    let err = restricted
+        .add_key::<User, _, _>("restrict_none_new_key", payload)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EPERM));
+
+    let err = restricted.link_key(&key).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EPERM));
+}
+
+#[test]
+fn restrict_to_keyrings() {
+    let mut keyring = utils::new_test_keyring();
+    let mut restricted = keyring.add_keyring("restrict_to_keyrings").unwrap();
+
+    let restriction = Restriction::ByType {
+        type_: "keyring".into(),
+        restriction: String::new(),
+    };
+    let err = restricted.restrict(&restriction).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOENT));
+}
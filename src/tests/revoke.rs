@@ -77,7 +77,9 @@ fn revoked_key() {
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
 
     let duration = Duration::from_secs(1);
-    let err = key_observer.set_timeout(duration).unwrap_err();
+    let err = key_observer
+        .set_timeout_duration(Some(duration))
+        .unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
 
     let err = key_observer.invalidate().unwrap_err();
@@ -99,7 +101,9 @@ fn revoked_keyring() {
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
 
     let duration = Duration::from_secs(1);
-    let err = keyring_observer.set_timeout(duration).unwrap_err();
+    let err = keyring_observer
+        .set_timeout_duration(Some(duration))
+        .unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
 
     let err = keyring_observer.invalidate().unwrap_err();
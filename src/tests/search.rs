@@ -26,8 +26,8 @@
 
 use std::iter;
 
-use crate::keytypes::User;
-use crate::Permission;
+use crate::keytypes::{logon, Logon, User};
+use crate::{Key, Permission};
 
 use super::utils;
 use super::utils::kernel::*;
@@ -701,3 +701,259 @@ fn search_and_find_keyring_no_write_perm() {
     assert!(keys.is_empty());
     assert!(keyrings.is_empty());
 }
+
+#[test]
+fn search_typed_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let new_keyring = keyring.add_keyring("search_typed_keyring").unwrap();
+
+    let found = keyring
+        .search_typed("keyring", "search_typed_keyring")
+        .unwrap();
+    assert!(found.refers_to(&new_keyring));
+
+    let expected = keyring
+        .search_for_keyring("search_typed_keyring", None)
+        .unwrap();
+    assert!(found.refers_to(&expected));
+}
+
+#[test]
+fn search_typed_nul_type() {
+    let keyring = utils::new_test_keyring();
+
+    let err = keyring
+        .search_typed("key\0ring", "search_typed_nul_type")
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn search_nul_description() {
+    let keyring = utils::new_test_keyring();
+
+    let err = keyring
+        .search_for_key::<User, _, _>("search\0description", None)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn request_nul_description() {
+    let err = Key::request::<User, _, _, _>("request\0description", None, None).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+
+    let err = Key::request::<User, _, _, _>("request_nul_info", "in\0fo", None).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn request_typed_nul_type() {
+    let err = Key::request_typed("us\0er", "request_typed_nul_type", None, None).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn request_typed() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("request_typed", payload)
+        .unwrap();
+
+    let found = Key::request_typed("user", "request_typed", None, None).unwrap();
+    assert!(found.same_key(&key));
+}
+
+#[test]
+fn request_negated_then_linked() {
+    let mut keyring = utils::new_test_keyring();
+    let mut store = keyring
+        .add_keyring("request_negated_then_linked_store")
+        .unwrap();
+    let description = "request_negated_then_linked";
+
+    // Without a handler which constructs the key, it is negated and linked into the keyring.
+    Key::request::<User, _, _, _>(description, "info", &mut *keyring).unwrap_err();
+    let err = Key::request::<User, _, _, _>(description, "info", &mut *keyring).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+
+    // Linking an existing key replaces the negative key, but the requester must search again.
+    let existing = store
+        .add_key::<User, _, _>(description, &b"payload"[..])
+        .unwrap();
+    keyring.link_key(&existing).unwrap();
+    let found = keyring.search::<User, _>(description).unwrap();
+    assert_eq!(found, existing);
+    let found = Key::request::<User, _, _, _>(description, None, None).unwrap();
+    assert_eq!(found, existing);
+}
+
+#[test]
+fn request_logon_key() {
+    let mut keyring = utils::new_test_keyring();
+    let description = logon::Description::new("request", "request_logon_key").unwrap();
+    let key = keyring
+        .add_key::<Logon, _, _>(&description, &b"payload"[..])
+        .unwrap();
+
+    let found = Key::request::<Logon, _, _, _>(&description, None, None).unwrap();
+    assert_eq!(found, key);
+
+    let found = Key::request_typed("logon", "request:request_logon_key", None, None).unwrap();
+    assert_eq!(found, key);
+}
+
+#[test]
+fn resolve_path_shallowest() {
+    let mut keyring = utils::new_test_keyring();
+    let mut first = keyring.add_keyring("resolve_path_first").unwrap();
+    let mut deep = first.add_keyring("resolve_path_deep").unwrap();
+    let mut second = keyring.add_keyring("resolve_path_second").unwrap();
+
+    let payload = &b"payload"[..];
+    deep.add_key::<User, _, _>("resolve_path_shallowest", payload)
+        .unwrap();
+    second
+        .add_key::<User, _, _>("resolve_path_shallowest", payload)
+        .unwrap();
+
+    let path = keyring
+        .resolve_path::<User>("resolve_path_shallowest")
+        .unwrap();
+    assert_eq!(
+        path,
+        Some(vec![keyring.serial(), first.serial(), deep.serial()])
+    );
+
+    let direct = keyring
+        .add_key::<User, _, _>("resolve_path_shallowest", payload)
+        .unwrap();
+    let path = keyring
+        .resolve_path::<User>("resolve_path_shallowest")
+        .unwrap();
+    assert_eq!(path, Some(vec![keyring.serial()]));
+    direct.invalidate().unwrap();
+}
+
+#[test]
+fn resolve_path_missing() {
+    let mut keyring = utils::new_test_keyring();
+    keyring.add_keyring("resolve_path_missing_child").unwrap();
+
+    let path = keyring
+        .resolve_path::<User>("resolve_path_missing")
+        .unwrap();
+    assert_eq!(path, None);
+}
+
+#[test]
+fn resolve_path_unreadable() {
+    let mut keyring = utils::new_test_keyring();
+    let mut hider = keyring
+        .add_keyring("resolve_path_unreadable_hider")
+        .unwrap();
+    let mut root = hider.add_keyring("resolve_path_unreadable_root").unwrap();
+    let mut child = root.add_keyring("resolve_path_unreadable_child").unwrap();
+    let mut key = child
+        .add_key::<User, _, _>("resolve_path_unreadable", &b"payload"[..])
+        .unwrap();
+
+    // Possessed keyrings may be read with only `search` permission, so hide the tree from the
+    // process keyrings and use the `user` permissions instead.
+    key.set_permissions(Permission::USER_VIEW | Permission::USER_READ | Permission::USER_SEARCH)
+        .unwrap();
+    child
+        .set_permissions(Permission::USER_VIEW | Permission::USER_SEARCH)
+        .unwrap();
+    root.set_permissions(Permission::USER_VIEW | Permission::USER_READ | Permission::USER_SEARCH)
+        .unwrap();
+    hider.set_permissions(Permission::POSSESSOR_VIEW).unwrap();
+
+    // The kernel finds the key, but the path to it cannot be read.
+    let found = root.search::<User, _>("resolve_path_unreadable").unwrap();
+    assert_eq!(found, key);
+    let err = root
+        .resolve_path::<User>("resolve_path_unreadable")
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EACCES));
+}
+
+#[test]
+fn search_logon_key() {
+    let mut keyring = utils::new_test_keyring();
+    let description = logon::Description::new("search", "search_logon_key").unwrap();
+    let key = keyring
+        .add_key::<Logon, _, _>(&description, &b"payload"[..])
+        .unwrap();
+
+    let found = keyring.search::<Logon, _>(&description).unwrap();
+    assert_eq!(found, key);
+
+    // Keys of other types with the same description are not found.
+    let err = keyring
+        .search::<User, _>("search:search_logon_key")
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn contains_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut child = keyring.add_keyring("contains_key_child").unwrap();
+    keyring
+        .add_key::<User, _, _>("contains_key", &b"payload"[..])
+        .unwrap();
+    child
+        .add_key::<User, _, _>("contains_key_nested", &b"payload"[..])
+        .unwrap();
+
+    assert!(keyring.contains_key::<User, _>("contains_key").unwrap());
+    assert!(!keyring
+        .contains_key::<Logon, _>(&logon::Description::new("contains", "key").unwrap())
+        .unwrap());
+    // Child keyrings are not searched.
+    assert!(!keyring
+        .contains_key::<User, _>("contains_key_nested")
+        .unwrap());
+
+    // Nothing was linked into the keyring.
+    let (keys, keyrings) = keyring.read().unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keyrings.len(), 1);
+}
+
+#[test]
+fn find_all() {
+    let mut keyring = utils::new_test_keyring();
+    let mut child = keyring.add_keyring("find_all_child").unwrap();
+    let mut unreadable = keyring.add_keyring("find_all_unreadable").unwrap();
+    let payload = &b"payload"[..];
+    let key = keyring.add_key::<User, _, _>("find_all", payload).unwrap();
+    let nested = child
+        .add_key::<User, _, _>("find_all_nested", payload)
+        .unwrap();
+    unreadable
+        .add_key::<User, _, _>("find_all_unreadable", payload)
+        .unwrap();
+    keyring
+        .add_key::<Logon, _, _>(
+            &logon::Description::new("find_all", "logon").unwrap(),
+            payload,
+        )
+        .unwrap();
+    let mut hidden = keyring
+        .add_key::<User, _, _>("find_all_hidden", payload)
+        .unwrap();
+    hidden
+        .set_permissions(Permission::POSSESSOR_SEARCH)
+        .unwrap();
+    // A key linked in twice is only found once.
+    child.link_key(&key).unwrap();
+    unreadable
+        .set_permissions(Permission::POSSESSOR_VIEW | Permission::USER_VIEW)
+        .unwrap();
+
+    let keys = keyring.find_all::<User>().unwrap();
+    assert_eq!(keys, [nested, key]);
+}
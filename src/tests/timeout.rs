@@ -35,7 +35,7 @@ use super::utils;
 fn invalid_key() {
     let mut key = utils::invalid_key();
     let duration = Duration::from_secs(1);
-    let err = key.set_timeout(duration).unwrap_err();
+    let err = key.set_timeout_duration(Some(duration)).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
@@ -43,7 +43,7 @@ fn invalid_key() {
 fn invalid_keyring() {
     let mut keyring = utils::invalid_keyring();
     let duration = Duration::from_secs(1);
-    let err = keyring.set_timeout(duration).unwrap_err();
+    let err = keyring.set_timeout_duration(Some(duration)).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
@@ -59,7 +59,7 @@ fn unlinked_key() {
     utils::wait_for_key_gc(&key);
 
     let duration = Duration::from_secs(1);
-    let err = key.set_timeout(duration).unwrap_err();
+    let err = key.set_timeout_duration(Some(duration)).unwrap_err();
     assert_eq!(err, errno::Errno(libc::ENOKEY));
 }
 
@@ -72,7 +72,7 @@ fn big_timeout_key() {
         .unwrap();
 
     let duration = Duration::from_secs(1024);
-    key.set_timeout(duration).unwrap();
+    key.set_timeout_duration(Some(duration)).unwrap();
 
     let actual_payload = key.read().unwrap();
     assert_eq!(payload, actual_payload.as_slice());
@@ -83,7 +83,7 @@ fn big_timeout_keyring() {
     let mut keyring = utils::new_test_keyring();
 
     let duration = Duration::from_secs(1024);
-    keyring.set_timeout(duration).unwrap();
+    keyring.set_timeout_duration(Some(duration)).unwrap();
 
     let (keys, keyrings) = keyring.read().unwrap();
     assert!(keys.is_empty());
@@ -101,7 +101,7 @@ fn expired_key() {
     let key_observer2 = key.clone();
 
     let duration = Duration::from_secs(1);
-    key.set_timeout(duration).unwrap();
+    key.set_timeout_duration(Some(duration)).unwrap();
 
     thread::sleep(duration);
     thread::sleep(duration);
@@ -109,7 +109,7 @@ fn expired_key() {
     let err = key.read().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
 
-    let err = key.set_timeout(duration).unwrap_err();
+    let err = key.set_timeout_duration(Some(duration)).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
 
     let err = key.invalidate().unwrap_err();
@@ -127,7 +127,7 @@ fn expired_keyring() {
     let keyring_observer = keyring.clone();
 
     let duration = Duration::from_secs(1);
-    keyring.set_timeout(duration).unwrap();
+    keyring.set_timeout_duration(Some(duration)).unwrap();
 
     thread::sleep(duration);
     thread::sleep(duration);
@@ -135,7 +135,7 @@ fn expired_keyring() {
     let err = keyring.read().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
 
-    let err = keyring.set_timeout(duration).unwrap_err();
+    let err = keyring.set_timeout_duration(Some(duration)).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
 
     let err = keyring.invalidate().unwrap_err();
@@ -144,3 +144,134 @@ fn expired_keyring() {
     let err = keyring_observer.revoke().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
 }
+
+#[test]
+fn timeout_remaining() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("timeout_remaining", payload)
+        .unwrap();
+
+    assert_eq!(key.timeout_remaining().unwrap(), None);
+
+    key.set_timeout_duration(Some(Duration::from_secs(30)))
+        .unwrap();
+    let remaining = key.timeout_remaining().unwrap().unwrap();
+    assert!(remaining <= Duration::from_secs(30));
+    assert!(remaining >= Duration::from_secs(25));
+}
+
+#[test]
+fn cap_timeout() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("cap_timeout", payload)
+        .unwrap();
+
+    key.set_timeout_duration(Some(Duration::from_secs(100)))
+        .unwrap();
+
+    key.cap_timeout(Duration::from_secs(10)).unwrap();
+    let capped = key.timeout_remaining().unwrap().unwrap();
+    assert!(capped <= Duration::from_secs(10));
+
+    key.cap_timeout(Duration::from_secs(50)).unwrap();
+    let uncapped = key.timeout_remaining().unwrap().unwrap();
+    assert!(uncapped <= capped);
+}
+
+#[test]
+fn cap_timeout_never_extends() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("cap_timeout_never_extends", payload)
+        .unwrap();
+
+    // The remaining time is reported as `1m`, so the key may expire after `max`.
+    key.set_timeout_duration(Some(Duration::from_secs(110)))
+        .unwrap();
+    let before = key.timeout_remaining().unwrap().unwrap();
+    assert_eq!(before, Duration::from_secs(60));
+
+    key.cap_timeout(Duration::from_secs(100)).unwrap();
+    let after = key.timeout_remaining().unwrap().unwrap();
+    assert!(after <= before);
+}
+
+#[test]
+fn cap_timeout_permanent() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("cap_timeout_permanent", payload)
+        .unwrap();
+
+    key.cap_timeout(Duration::from_secs(10)).unwrap();
+    let capped = key.timeout_remaining().unwrap().unwrap();
+    assert!(capped <= Duration::from_secs(10));
+}
+
+#[test]
+fn clear_timeout() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("clear_timeout", payload)
+        .unwrap();
+
+    key.set_timeout_duration(Some(Duration::from_secs(100)))
+        .unwrap();
+    assert!(key.timeout_remaining().unwrap().is_some());
+
+    key.set_timeout_duration(None).unwrap();
+    assert_eq!(key.timeout_remaining().unwrap(), None);
+}
+
+#[test]
+fn sub_second_timeout() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("sub_second_timeout", payload)
+        .unwrap();
+
+    // Rounds up to one second rather than clearing the timeout.
+    key.set_timeout_duration(Some(Duration::from_millis(10)))
+        .unwrap();
+    assert!(key.timeout_remaining().unwrap().is_some());
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_set_timeout() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("deprecated_set_timeout", payload)
+        .unwrap();
+
+    key.set_timeout(Duration::from_secs(100)).unwrap();
+    assert!(key.timeout_remaining().unwrap().is_some());
+
+    keyring.set_timeout(Duration::from_secs(100)).unwrap();
+    assert!(utils::keyring_as_key(&keyring)
+        .timeout_remaining()
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn keyring_timeout_remaining() {
+    let mut keyring = utils::new_test_keyring();
+    let mut child = keyring.add_keyring("keyring_timeout_remaining").unwrap();
+    assert_eq!(child.timeout_remaining().unwrap(), None);
+
+    child
+        .set_timeout_duration(Some(Duration::from_secs(100)))
+        .unwrap();
+    let remaining = child.timeout_remaining().unwrap().unwrap();
+    assert!(remaining <= Duration::from_secs(100));
+}
@@ -226,3 +226,50 @@ fn unlink_keyring() {
     assert!(keys.is_empty());
     assert!(keyrings.is_empty());
 }
+
+#[test]
+fn retain_keys() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let keep = keyring
+        .add_key::<User, _, _>("retain_keys_keep", payload)
+        .unwrap();
+    keyring
+        .add_key::<User, _, _>("retain_keys_drop1", payload)
+        .unwrap();
+    keyring
+        .add_key::<User, _, _>("retain_keys_drop2", payload)
+        .unwrap();
+
+    let removed = keyring
+        .retain(|key| {
+            key.description()
+                .map(|desc| desc.description.ends_with("_keep"))
+                .unwrap_or(true)
+        })
+        .unwrap();
+    assert_eq!(removed, 2);
+
+    let (keys, keyrings) = keyring.read().unwrap();
+    assert_eq!(keys, [keep]);
+    assert!(keyrings.is_empty());
+}
+
+#[test]
+fn retain_invalidated_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("retain_invalidated_key", payload)
+        .unwrap();
+
+    // A key which disappears before it is unlinked is not an error.
+    keyring
+        .retain(|_| {
+            key.clone().invalidate().unwrap();
+            utils::wait_for_key_gc(&key);
+            false
+        })
+        .unwrap();
+    assert!(!keyring.contains(&key).unwrap());
+}
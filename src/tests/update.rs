@@ -38,6 +38,14 @@ fn keyring() {
     assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
 }
 
+#[test]
+fn keyring_update() {
+    let mut keyring = utils::new_test_keyring();
+
+    let err = keyring.update(b"payload").unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+}
+
 #[test]
 fn invalid_key() {
     let mut key = utils::invalid_key();
@@ -78,3 +86,110 @@ fn user_key() {
     let actual_payload = key.read().unwrap();
     assert_eq!(payload, actual_payload.as_slice());
 }
+
+#[test]
+fn rotate() {
+    let mut keyring = utils::new_test_keyring();
+    let current = keyring
+        .add_key::<User, _, _>("rotate.current", &b"old"[..])
+        .unwrap();
+    keyring
+        .add_key::<User, _, _>("rotate.next", &b"new"[..])
+        .unwrap();
+
+    let rotated = keyring
+        .rotate("rotate.current", "rotate.next", true)
+        .unwrap();
+    assert_eq!(rotated, current);
+    assert_eq!(rotated.read().unwrap(), b"new");
+
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [current]);
+}
+
+#[test]
+fn rotate_missing_next() {
+    let mut keyring = utils::new_test_keyring();
+    let err = keyring
+        .rotate(
+            "rotate_missing_next.current",
+            "rotate_missing_next.next",
+            true,
+        )
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn rotate_keep_next() {
+    let mut keyring = utils::new_test_keyring();
+    let current = keyring
+        .add_key::<User, _, _>("rotate_keep_next.current", &b"old"[..])
+        .unwrap();
+    let next = keyring
+        .add_key::<User, _, _>("rotate_keep_next.next", &b"new"[..])
+        .unwrap();
+
+    let rotated = keyring
+        .rotate("rotate_keep_next.current", "rotate_keep_next.next", false)
+        .unwrap();
+    assert_eq!(rotated, current);
+    assert_eq!(rotated.read().unwrap(), b"new");
+
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [current, next]);
+}
+
+#[test]
+fn rotate_nested_next() {
+    let mut keyring = utils::new_test_keyring();
+    let mut child = keyring.add_keyring("rotate_nested_next").unwrap();
+    let current = keyring
+        .add_key::<User, _, _>("rotate_nested_next.current", &b"old"[..])
+        .unwrap();
+    let next = child
+        .add_key::<User, _, _>("rotate_nested_next.next", &b"new"[..])
+        .unwrap();
+
+    // Keys in nested keyrings are not used, so nothing is changed.
+    let err = keyring
+        .rotate(
+            "rotate_nested_next.current",
+            "rotate_nested_next.next",
+            true,
+        )
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert_eq!(current.read().unwrap(), b"old");
+    assert!(child.contains(&next).unwrap());
+}
+
+#[test]
+fn adopt_or_add_new() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .adopt_or_add::<User, _, _>("adopt_or_add_new", &b"payload"[..], None)
+        .unwrap();
+
+    assert_eq!(key.read().unwrap(), b"payload");
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [key]);
+}
+
+#[test]
+fn adopt_or_add_existing() {
+    let mut keyring = utils::new_test_keyring();
+    let mut other = keyring.add_keyring("adopt_or_add_existing_other").unwrap();
+    let existing = other
+        .add_key::<User, _, _>("adopt_or_add_existing", &b"payload"[..])
+        .unwrap();
+
+    let key = keyring
+        .adopt_or_add::<User, _, _>("unused", &b"updated"[..], Some(&existing))
+        .unwrap();
+    assert_eq!(key, existing);
+    assert_eq!(existing.read().unwrap(), b"updated");
+
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [existing]);
+}
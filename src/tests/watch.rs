@@ -0,0 +1,212 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::ops::ControlFlow;
+
+use crate::keytypes::User;
+use crate::{supports_notifications, KeyEvent, WatchQueue};
+
+use super::utils;
+
+fn watch_queue() -> Option<WatchQueue> {
+    match WatchQueue::new() {
+        Ok(queue) => Some(queue),
+        Err(errno::Errno(libc::EOPNOTSUPP)) => {
+            eprintln!("This kernel does not support key notifications; skipping.");
+            None
+        },
+        Err(err) => panic!("failed to create a watch queue: {}", err),
+    }
+}
+
+#[test]
+fn watch_queue_unsupported() {
+    let res = WatchQueue::new();
+    if supports_notifications() {
+        assert!(res.is_ok());
+    } else {
+        assert_eq!(res.unwrap_err(), errno::Errno(libc::EOPNOTSUPP));
+    }
+}
+
+#[test]
+fn watch_updates() {
+    let mut queue = if let Some(queue) = watch_queue() {
+        queue
+    } else {
+        return;
+    };
+
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("watch_updates", payload)
+        .unwrap();
+    queue.watch_key(&key, 1).unwrap();
+
+    key.update::<User, _>(&b"first"[..]).unwrap();
+    key.update::<User, _>(&b"second"[..]).unwrap();
+
+    let mut updates = 0;
+    queue
+        .run(|event| {
+            assert_eq!(event, KeyEvent::Updated(key.serial()));
+            updates += 1;
+            if updates == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .unwrap();
+    assert_eq!(updates, 2);
+}
+
+#[test]
+fn watch_all_next_change() {
+    let mut queue = if let Some(queue) = watch_queue() {
+        queue
+    } else {
+        return;
+    };
+
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let first = keyring
+        .add_key::<User, _, _>("watch_all_next_change_first", payload)
+        .unwrap();
+    let mut second = keyring
+        .add_key::<User, _, _>("watch_all_next_change_second", payload)
+        .unwrap();
+    queue.watch_all(&[&first, &second]).unwrap();
+
+    second.update::<User, _>(&b"updated"[..]).unwrap();
+
+    let (serial, event) = queue.next_change().unwrap();
+    assert_eq!(serial, second.serial());
+    assert_eq!(event, KeyEvent::Updated(second.serial()));
+}
+
+#[test]
+fn key_watch() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring.add_key::<User, _, _>("key_watch", payload).unwrap();
+
+    let mut watch = match key.watch() {
+        Ok(watch) => watch,
+        Err(err) => {
+            assert!(!supports_notifications());
+            assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+            return;
+        },
+    };
+
+    key.update::<User, _>(&b"updated"[..]).unwrap();
+    assert_eq!(watch.recv().unwrap(), KeyEvent::Updated(key.serial()));
+
+    key.clone().revoke().unwrap();
+    assert_eq!(watch.recv().unwrap(), KeyEvent::Revoked(key.serial()));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn key_watch_poll_event() {
+    use std::os::unix::io::AsRawFd;
+    use std::ptr;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("key_watch_poll_event", payload)
+        .unwrap();
+
+    let mut watch = match key.watch() {
+        Ok(watch) => watch,
+        Err(err) => {
+            assert!(!supports_notifications());
+            assert_eq!(err, errno::Errno(libc::EOPNOTSUPP));
+            return;
+        },
+    };
+    assert!(watch.as_raw_fd() >= 0);
+
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    assert!(watch.poll_event(&mut cx).is_pending());
+
+    key.update::<User, _>(&b"updated"[..]).unwrap();
+    match watch.poll_event(&mut cx) {
+        Poll::Ready(event) => assert_eq!(event.unwrap(), KeyEvent::Updated(key.serial())),
+        Poll::Pending => panic!("expected an event to be available"),
+    }
+    assert!(watch.poll_event(&mut cx).is_pending());
+}
+
+#[test]
+fn unwatch_key() {
+    let mut queue = if let Some(queue) = watch_queue() {
+        queue
+    } else {
+        return;
+    };
+
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut unwatched = keyring
+        .add_key::<User, _, _>("unwatch_key_unwatched", payload)
+        .unwrap();
+    let mut watched = keyring
+        .add_key::<User, _, _>("unwatch_key_watched", payload)
+        .unwrap();
+    match queue.watch_all(&[&unwatched, &watched]) {
+        Ok(()) => {},
+        Err(errno::Errno(libc::ENOPKG)) => {
+            eprintln!("This kernel does not support key notifications; skipping.");
+            return;
+        },
+        Err(err) => panic!("failed to watch keys: {}", err),
+    }
+    queue.unwatch_key(&unwatched).unwrap();
+
+    // Events arrive in order, so the update of the unwatched key would be seen first.
+    unwatched.update::<User, _>(&b"updated"[..]).unwrap();
+    watched.update::<User, _>(&b"updated"[..]).unwrap();
+
+    let (serial, event) = queue.next_change().unwrap();
+    assert_eq!(serial, watched.serial());
+    assert_eq!(event, KeyEvent::Updated(watched.serial()));
+}
@@ -0,0 +1,268 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Support for programs invoked to construct keys.
+//!
+//! When a key is requested which does not exist, the kernel runs `/sbin/request-key` with
+//! information about the request. It, in turn, runs the program configured for the key in
+//! `/etc/request-key.conf`. The kernel invokes `/sbin/request-key` as:
+//!
+//! ```text
+//! /sbin/request-key <op> <key> <uid> <gid> <thread keyring> <process keyring> <session keyring>
+//! ```
+//!
+//! and the same arguments may be passed to a configured program using `%o %k %u %g %T %P %S`.
+//! The callout information may also be passed as an additional argument using `%c`.
+
+use std::env;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+
+use keyutils_raw::*;
+
+use crate::{Key, KeyManager, Keyring, KeyringSerial, Result};
+
+/// The context of a request to construct a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// The operation being requested (usually `create`).
+    pub operation: String,
+    /// The key to construct.
+    pub key: Key,
+    /// The user ID of the requesting process.
+    pub uid: libc::uid_t,
+    /// The group ID of the requesting process.
+    pub gid: libc::gid_t,
+    /// The thread keyring of the requesting process.
+    pub thread_keyring: Option<Keyring>,
+    /// The process keyring of the requesting process.
+    pub process_keyring: Option<Keyring>,
+    /// The session keyring of the requesting process.
+    pub session_keyring: Option<Keyring>,
+    /// The callout information given to the request.
+    pub callout_info: Option<Vec<u8>>,
+}
+
+fn parse_arg<T, S>(arg: Option<S>) -> Result<T>
+where
+    T: std::str::FromStr,
+    S: AsRef<str>,
+{
+    arg.ok_or(errno::Errno(libc::EINVAL))?
+        .as_ref()
+        .parse()
+        .map_err(|_| errno::Errno(libc::EINVAL))
+}
+
+fn parse_keyring<S>(arg: Option<S>) -> Result<Option<Keyring>>
+where
+    S: AsRef<str>,
+{
+    // Keyrings which do not exist are given as `0`.
+    parse_arg(arg).map(|id| KeyringSerial::new(id).map(Keyring::new_impl))
+}
+
+impl RequestContext {
+    /// Parse the context from the arguments of a `request-key` invocation.
+    ///
+    /// The arguments should not include the program name. Missing or malformed arguments result
+    /// in `EINVAL`.
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut args = args.into_iter();
+
+        let operation = args
+            .next()
+            .ok_or(errno::Errno(libc::EINVAL))?
+            .as_ref()
+            .into();
+        let key = KeyringSerial::new(parse_arg(args.next())?)
+            .map(Key::new_impl)
+            .ok_or(errno::Errno(libc::EINVAL))?;
+        let uid = parse_arg(args.next())?;
+        let gid = parse_arg(args.next())?;
+        let thread_keyring = parse_keyring(args.next())?;
+        let process_keyring = parse_keyring(args.next())?;
+        let session_keyring = parse_keyring(args.next())?;
+        let callout_info = args.next().map(|arg| arg.as_ref().as_bytes().into());
+
+        Ok(RequestContext {
+            operation,
+            key,
+            uid,
+            gid,
+            thread_keyring,
+            process_keyring,
+            session_keyring,
+            callout_info,
+        })
+    }
+
+    /// Parse the context from the raw arguments of a `request-key` invocation.
+    ///
+    /// This is the same as `from_args` except that the callout information may contain arbitrary
+    /// bytes. Other arguments which are not valid UTF-8 result in `EINVAL`.
+    pub fn from_args_os<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        // The number of arguments before the callout information.
+        const REQUIRED_ARGS: usize = 7;
+
+        let mut args = args.into_iter();
+        let required = args
+            .by_ref()
+            .take(REQUIRED_ARGS)
+            .map(|arg| arg.as_ref().to_str().map(String::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(errno::Errno(libc::EINVAL))?;
+
+        let mut context = Self::from_args(required)?;
+        context.callout_info = args.next().map(|arg| arg.as_ref().as_bytes().into());
+        Ok(context)
+    }
+
+    /// Assume authority over the key for the current thread.
+    ///
+    /// This is required before the key may be instantiated or rejected. If the callout
+    /// information was not given as an argument, it is read from the authorization key.
+    pub fn assume_authority(&mut self) -> Result<()> {
+        keyctl_assume_authority(Some(self.key.serial()))?;
+        if self.callout_info.is_none() {
+            let auth_key = KeyManager::request_key_auth_key(false)?;
+            self.callout_info = Some(auth_key.read()?);
+        }
+        Ok(())
+    }
+}
+
+/// Read the request context for the current process.
+///
+/// The context is parsed from the program's arguments (see `RequestContext::from_args_os`). No
+/// authority over the key is assumed; use `RequestContext::assume_authority` for that (which
+/// also reads the callout information if it was not given as an argument).
+pub fn from_env() -> Result<RequestContext> {
+    RequestContext::from_args_os(env::args_os().skip(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    use super::RequestContext;
+
+    #[test]
+    fn test_from_args() {
+        let args = ["create", "1234", "1000", "100", "0", "56", "78"];
+        let context = RequestContext::from_args(args.iter()).unwrap();
+
+        assert_eq!(context.operation, "create");
+        assert_eq!(context.key.serial().get(), 1234);
+        assert_eq!(context.uid, 1000);
+        assert_eq!(context.gid, 100);
+        assert_eq!(context.thread_keyring, None);
+        assert_eq!(
+            context
+                .process_keyring
+                .map(|keyring| keyring.serial().get()),
+            Some(56),
+        );
+        assert_eq!(
+            context
+                .session_keyring
+                .map(|keyring| keyring.serial().get()),
+            Some(78),
+        );
+        assert_eq!(context.callout_info, None);
+    }
+
+    #[test]
+    fn test_from_args_callout_info() {
+        let args = ["create", "1234", "1000", "100", "12", "56", "78", "info"];
+        let context = RequestContext::from_args(args.iter()).unwrap();
+
+        assert_eq!(
+            context.thread_keyring.map(|keyring| keyring.serial().get()),
+            Some(12),
+        );
+        assert_eq!(context.callout_info, Some(b"info".to_vec()));
+    }
+
+    #[test]
+    fn test_from_args_invalid() {
+        let check = |args: &[&str]| {
+            let err = RequestContext::from_args(args).unwrap_err();
+            assert_eq!(err, errno::Errno(libc::EINVAL));
+        };
+
+        check(&[]);
+        check(&["create"]);
+        check(&["create", "0", "1000", "100", "0", "0", "0"]);
+        check(&["create", "key", "1000", "100", "0", "0", "0"]);
+        check(&["create", "1234", "user", "100", "0", "0", "0"]);
+        check(&["create", "1234", "1000", "100", "0", "0"]);
+    }
+
+    #[test]
+    fn test_from_args_os() {
+        let args = ["create", "1234", "1000", "100", "12", "56", "78"];
+        let context = RequestContext::from_args_os(args.iter()).unwrap();
+        assert_eq!(context, RequestContext::from_args(args.iter()).unwrap());
+
+        let info = OsStr::from_bytes(b"\xffinfo");
+        let args = args.iter().map(OsStr::new).chain(Some(info));
+        let context = RequestContext::from_args_os(args).unwrap();
+        assert_eq!(context.callout_info, Some(b"\xffinfo".to_vec()));
+    }
+
+    #[test]
+    fn test_from_args_os_invalid() {
+        let args = ["create", "1234", "1000", "100", "12", "56", "78"];
+        for i in 0..args.len() {
+            let args = args.iter().enumerate().map(|(j, arg)| {
+                if i == j {
+                    OsStr::from_bytes(b"\xff")
+                } else {
+                    OsStr::new(arg)
+                }
+            });
+            let err = RequestContext::from_args_os(args).unwrap_err();
+            assert_eq!(err, errno::Errno(libc::EINVAL));
+        }
+    }
+
+    #[test]
+    fn test_from_env() {
+        // The test harness is not invoked as `request-key`, but this must not panic.
+        let err = super::from_env().unwrap_err();
+        assert_eq!(err, errno::Errno(libc::EINVAL));
+    }
+}
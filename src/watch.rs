@@ -0,0 +1,597 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Key notifications
+//!
+//! Since Linux 5.8, the kernel can report changes to keys through a notification queue.
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::mem;
+use std::ops::ControlFlow;
+#[cfg(feature = "async")]
+use std::os::unix::io::AsRawFd;
+use std::os::unix::io::RawFd;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+use keyutils_raw::*;
+
+use crate::{capabilities, Key, Keyring, Result};
+
+// Notification pipes are created by passing `O_EXCL` to `pipe2`.
+const O_NOTIFICATION_PIPE: libc::c_int = libc::O_EXCL;
+// _IO('W', 0x60)
+const IOC_WATCH_QUEUE_SET_SIZE: libc::c_ulong = 0x5760;
+// The number of notifications the queue may hold.
+const WATCH_QUEUE_SIZE: libc::c_ulong = 256;
+
+const WATCH_TYPE_META: u32 = 0;
+const WATCH_TYPE_KEY_NOTIFY: u32 = 1;
+
+const WATCH_META_REMOVAL_NOTIFICATION: u8 = 0;
+const WATCH_META_LOSS_NOTIFICATION: u8 = 1;
+
+const NOTIFY_KEY_INSTANTIATED: u8 = 0;
+const NOTIFY_KEY_UPDATED: u8 = 1;
+const NOTIFY_KEY_LINKED: u8 = 2;
+const NOTIFY_KEY_UNLINKED: u8 = 3;
+const NOTIFY_KEY_CLEARED: u8 = 4;
+const NOTIFY_KEY_REVOKED: u8 = 5;
+const NOTIFY_KEY_INVALIDATED: u8 = 6;
+const NOTIFY_KEY_SETATTR: u8 = 7;
+
+const WATCH_INFO_LENGTH: u32 = 0x7f;
+
+// The largest possible notification is 127 bytes, so reading a page at a time is plenty.
+const READ_SIZE: usize = 4096;
+
+/// An event reported for a watched key or keyring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum KeyEvent {
+    /// The key was instantiated.
+    Instantiated(KeyringSerial),
+    /// The payload of the key was updated.
+    Updated(KeyringSerial),
+    /// A key was linked into a keyring.
+    Linked {
+        /// The keyring which was modified.
+        keyring: KeyringSerial,
+        /// The key which was linked.
+        key: KeyringSerial,
+    },
+    /// A key was unlinked from a keyring.
+    Unlinked {
+        /// The keyring which was modified.
+        keyring: KeyringSerial,
+        /// The key which was unlinked.
+        key: KeyringSerial,
+    },
+    /// The keyring was cleared.
+    Cleared(KeyringSerial),
+    /// The key was revoked.
+    Revoked(KeyringSerial),
+    /// The key was invalidated.
+    Invalidated(KeyringSerial),
+    /// The attributes of the key were changed.
+    AttributesChanged(KeyringSerial),
+    /// The watch was removed because the watched key was destroyed.
+    Removed,
+    /// Notifications were lost because the queue was full.
+    Overrun,
+}
+
+impl KeyEvent {
+    /// The serial of the watched key or keyring which reported the event.
+    ///
+    /// For link changes, this is the keyring. Returns `None` for `Removed` and `Overrun` since
+    /// these are not associated with a specific key.
+    pub fn serial(&self) -> Option<KeyringSerial> {
+        match *self {
+            KeyEvent::Instantiated(serial)
+            | KeyEvent::Updated(serial)
+            | KeyEvent::Cleared(serial)
+            | KeyEvent::Revoked(serial)
+            | KeyEvent::Invalidated(serial)
+            | KeyEvent::AttributesChanged(serial) => Some(serial),
+            KeyEvent::Linked {
+                keyring, ..
+            }
+            | KeyEvent::Unlinked {
+                keyring, ..
+            } => Some(keyring),
+            KeyEvent::Removed | KeyEvent::Overrun => None,
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_ne_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn key_event(subtype: u8, record: &[u8]) -> Option<KeyEvent> {
+    // The key ID and auxiliary data follow the header.
+    if record.len() < 16 {
+        return None;
+    }
+    let key = KeyringSerial::new(read_u32(&record[8..]) as i32)?;
+    let aux = || KeyringSerial::new(read_u32(&record[12..]) as i32);
+
+    Some(match subtype {
+        NOTIFY_KEY_INSTANTIATED => KeyEvent::Instantiated(key),
+        NOTIFY_KEY_UPDATED => KeyEvent::Updated(key),
+        NOTIFY_KEY_LINKED => {
+            KeyEvent::Linked {
+                keyring: key,
+                key: aux()?,
+            }
+        },
+        NOTIFY_KEY_UNLINKED => {
+            KeyEvent::Unlinked {
+                keyring: key,
+                key: aux()?,
+            }
+        },
+        NOTIFY_KEY_CLEARED => KeyEvent::Cleared(key),
+        NOTIFY_KEY_REVOKED => KeyEvent::Revoked(key),
+        NOTIFY_KEY_INVALIDATED => KeyEvent::Invalidated(key),
+        NOTIFY_KEY_SETATTR => KeyEvent::AttributesChanged(key),
+        _ => return None,
+    })
+}
+
+// Parse the complete notifications at the start of `buffer`.
+//
+// Returns the parsed events and the number of bytes consumed. Unknown notifications are skipped.
+fn parse_events(buffer: &[u8]) -> Result<(Vec<KeyEvent>, usize)> {
+    let header_size = 2 * mem::size_of::<u32>();
+    let mut events = Vec::new();
+    let mut offset = 0;
+
+    while buffer.len() - offset >= header_size {
+        let record = &buffer[offset..];
+        let type_info = read_u32(record);
+        let info = read_u32(&record[4..]);
+        let len = (info & WATCH_INFO_LENGTH) as usize;
+        if len < header_size {
+            return Err(errno::Errno(libc::EBADMSG));
+        }
+        if record.len() < len {
+            // Wait for the rest of the notification.
+            break;
+        }
+        let record = &record[..len];
+
+        let type_ = type_info & 0x00ff_ffff;
+        let subtype = (type_info >> 24) as u8;
+        let event = match (type_, subtype) {
+            (WATCH_TYPE_META, WATCH_META_REMOVAL_NOTIFICATION) => Some(KeyEvent::Removed),
+            (WATCH_TYPE_META, WATCH_META_LOSS_NOTIFICATION) => Some(KeyEvent::Overrun),
+            (WATCH_TYPE_KEY_NOTIFY, subtype) => key_event(subtype, record),
+            _ => None,
+        };
+        events.extend(event);
+        offset += len;
+    }
+
+    Ok((events, offset))
+}
+
+/// Whether the running kernel supports key change notifications.
+///
+/// This is a shortcut for checking `capabilities()`.
+pub fn supports_notifications() -> bool {
+    capabilities().unwrap_or_default().supports_notifications()
+}
+
+/// A queue of notifications about changes to keys.
+///
+/// Requires Linux 5.8 or newer with `CONFIG_WATCH_QUEUE` enabled.
+#[derive(Debug)]
+pub struct WatchQueue {
+    read_fd: RawFd,
+    write_fd: RawFd,
+    buffer: Vec<u8>,
+    pending: VecDeque<KeyEvent>,
+}
+
+impl WatchQueue {
+    /// Create a new notification queue.
+    ///
+    /// Returns `EOPNOTSUPP` if the kernel does not support key notifications (Linux 5.8 or newer
+    /// with `CONFIG_KEY_NOTIFICATIONS` is required).
+    pub fn new() -> Result<Self> {
+        if !supports_notifications() {
+            return Err(errno::Errno(libc::EOPNOTSUPP));
+        }
+
+        let mut fds = [0; 2];
+        // Close the queue in child processes so that they do not keep the watches alive.
+        let ret = unsafe { libc::pipe2(fds.as_mut_ptr(), O_NOTIFICATION_PIPE | libc::O_CLOEXEC) };
+        if ret < 0 {
+            return Err(errno::errno());
+        }
+        let queue = WatchQueue {
+            read_fd: fds[0],
+            write_fd: fds[1],
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+        };
+
+        let ret = unsafe { libc::ioctl(queue.read_fd, IOC_WATCH_QUEUE_SET_SIZE, WATCH_QUEUE_SIZE) };
+        if ret < 0 {
+            return Err(errno::errno());
+        }
+
+        Ok(queue)
+    }
+
+    fn watch(&mut self, id: KeyringSerial, watch_id: u8) -> Result<()> {
+        keyctl_watch_key(id, self.read_fd, watch_id.into())
+    }
+
+    fn unwatch(&mut self, id: KeyringSerial) -> Result<()> {
+        // The kernel looks up the queue before noticing that the watch is being removed, so it
+        // must still be given.
+        keyctl_watch_key(id, self.read_fd, -1)
+    }
+
+    /// Report changes to `key` through the queue.
+    ///
+    /// The `watch_id` is used by the kernel to distinguish watches on the same queue. Requires
+    /// the `view` permission on the key.
+    pub fn watch_key(&mut self, key: &Key, watch_id: u8) -> Result<()> {
+        self.watch(key.serial(), watch_id)
+    }
+
+    /// Report changes to `keyring` through the queue.
+    ///
+    /// The `watch_id` is used by the kernel to distinguish watches on the same queue. Requires
+    /// the `view` permission on the keyring.
+    pub fn watch_keyring(&mut self, keyring: &Keyring, watch_id: u8) -> Result<()> {
+        self.watch(keyring.serial(), watch_id)
+    }
+
+    /// Report changes to all of `keys` through the queue.
+    ///
+    /// Every key is watched using a `watch_id` of `0`. Requires the `view` permission on the
+    /// keys.
+    pub fn watch_all(&mut self, keys: &[&Key]) -> Result<()> {
+        keys.iter().try_for_each(|key| self.watch_key(key, 0))
+    }
+
+    /// Stop reporting changes to `key`.
+    pub fn unwatch_key(&mut self, key: &Key) -> Result<()> {
+        self.unwatch(key.serial())
+    }
+
+    /// Stop reporting changes to `keyring`.
+    pub fn unwatch_keyring(&mut self, keyring: &Keyring) -> Result<()> {
+        self.unwatch(keyring.serial())
+    }
+
+    // Read and parse what is available from the queue with a single `read`.
+    //
+    // Blocks if nothing is available. Returns no events for an interrupted read or when only part
+    // of a notification has been read.
+    fn read_once(&mut self) -> Result<Vec<KeyEvent>> {
+        let start = self.buffer.len();
+        self.buffer.resize(start + READ_SIZE, 0);
+        let ret = unsafe {
+            libc::read(
+                self.read_fd,
+                self.buffer[start..].as_mut_ptr() as *mut libc::c_void,
+                READ_SIZE,
+            )
+        };
+        if ret < 0 {
+            self.buffer.truncate(start);
+            let err = errno::errno();
+            if err == errno::Errno(libc::EINTR) {
+                return Ok(Vec::new());
+            }
+            return Err(err);
+        }
+        self.buffer.truncate(start + ret as usize);
+
+        let (events, consumed) = parse_events(&self.buffer)?;
+        self.buffer.drain(..consumed);
+        Ok(events)
+    }
+
+    /// Block until events are available and return them.
+    pub fn read_events(&mut self) -> Result<Vec<KeyEvent>> {
+        if !self.pending.is_empty() {
+            return Ok(self.pending.drain(..).collect());
+        }
+
+        loop {
+            let events = self.read_once()?;
+            if !events.is_empty() {
+                return Ok(events);
+            }
+        }
+    }
+
+    // Whether reading from the queue would not block.
+    #[cfg(feature = "async")]
+    fn is_readable(&self) -> Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.read_fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ret = unsafe { libc::poll(&mut pollfd, 1, 0) };
+        if ret < 0 {
+            let err = errno::errno();
+            if err == errno::Errno(libc::EINTR) {
+                return Ok(false);
+            }
+            return Err(err);
+        }
+        Ok(ret > 0)
+    }
+
+    /// Return the next event if one is available without blocking.
+    ///
+    /// Returns `Poll::Pending` if no complete notification is available. No wakeup is arranged
+    /// through `cx`; instead, the file descriptor of the queue (see `AsRawFd`) must be registered
+    /// for readability with the reactor of the async runtime, which wakes the task when more
+    /// notifications arrive.
+    #[cfg(feature = "async")]
+    pub fn poll_event(&mut self, _cx: &mut Context) -> Poll<Result<KeyEvent>> {
+        if self.pending.is_empty() {
+            match self.is_readable() {
+                Ok(true) => {},
+                Ok(false) => return Poll::Pending,
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+            match self.read_once() {
+                Ok(events) => self.pending.extend(events),
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+        self.pending
+            .pop_front()
+            .map_or(Poll::Pending, |event| Poll::Ready(Ok(event)))
+    }
+
+    /// Block until the next event for a watched key or keyring and return it with its serial.
+    ///
+    /// See `KeyEvent::serial` for the serial which is reported. `Removed` events are skipped and
+    /// `ENOBUFS` is returned if notifications were lost.
+    pub fn next_change(&mut self) -> Result<(KeyringSerial, KeyEvent)> {
+        loop {
+            if self.pending.is_empty() {
+                let events = self.read_events()?;
+                self.pending.extend(events);
+            }
+
+            while let Some(event) = self.pending.pop_front() {
+                if event == KeyEvent::Overrun {
+                    return Err(errno::Errno(libc::ENOBUFS));
+                }
+                if let Some(serial) = event.serial() {
+                    return Ok((serial, event));
+                }
+            }
+        }
+    }
+
+    /// Block reading events from the queue and pass them to `f` until it breaks.
+    pub fn run<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(KeyEvent) -> ControlFlow<()>,
+    {
+        loop {
+            for event in self.read_events()? {
+                if let ControlFlow::Break(()) = f(event) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// A watch on a single key.
+///
+/// Obtained from `Key::watch`. The watch is removed when this is dropped.
+#[derive(Debug)]
+pub struct KeyWatch {
+    queue: WatchQueue,
+}
+
+impl KeyWatch {
+    pub(crate) fn new(key: &Key) -> Result<Self> {
+        let mut queue = WatchQueue::new()?;
+        queue.watch_key(key, 0)?;
+        Ok(KeyWatch {
+            queue,
+        })
+    }
+
+    /// Return the next event for the key if one is available without blocking.
+    ///
+    /// See `WatchQueue::poll_event`.
+    #[cfg(feature = "async")]
+    pub fn poll_event(&mut self, cx: &mut Context) -> Poll<Result<KeyEvent>> {
+        self.queue.poll_event(cx)
+    }
+
+    /// Block until the next event for the key and return it.
+    pub fn recv(&mut self) -> Result<KeyEvent> {
+        if self.queue.pending.is_empty() {
+            let events = self.queue.read_events()?;
+            self.queue.pending.extend(events);
+        }
+        Ok(self
+            .queue
+            .pending
+            .pop_front()
+            .expect("reading events should return at least one event"))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsRawFd for WatchQueue {
+    fn as_raw_fd(&self) -> RawFd {
+        self.read_fd
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsRawFd for KeyWatch {
+    fn as_raw_fd(&self) -> RawFd {
+        self.queue.as_raw_fd()
+    }
+}
+
+impl Drop for WatchQueue {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.read_fd);
+            libc::close(self.write_fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KeyringSerial;
+
+    use super::*;
+
+    fn record(type_: u32, subtype: u8, payload: &[u32]) -> Vec<u8> {
+        let len = 8 + 4 * payload.len() as u32;
+        let mut record = Vec::new();
+        record.extend_from_slice(&(type_ | (u32::from(subtype) << 24)).to_ne_bytes());
+        record.extend_from_slice(&len.to_ne_bytes());
+        for word in payload {
+            record.extend_from_slice(&word.to_ne_bytes());
+        }
+        record
+    }
+
+    fn serial(id: i32) -> KeyringSerial {
+        KeyringSerial::new(id).unwrap()
+    }
+
+    #[test]
+    fn test_queue_cloexec() {
+        let queue = match WatchQueue::new() {
+            Ok(queue) => queue,
+            Err(errno::Errno(libc::EOPNOTSUPP)) => return,
+            Err(err) => panic!("failed to create a watch queue: {}", err),
+        };
+
+        for &fd in &[queue.read_fd, queue.write_fd] {
+            let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+            assert!(flags >= 0);
+            assert_ne!(flags & libc::FD_CLOEXEC, 0);
+        }
+    }
+
+    #[test]
+    fn test_event_serial() {
+        assert_eq!(KeyEvent::Updated(serial(10)).serial(), Some(serial(10)));
+        assert_eq!(
+            KeyEvent::Linked {
+                keyring: serial(20),
+                key: serial(30),
+            }
+            .serial(),
+            Some(serial(20)),
+        );
+        assert_eq!(KeyEvent::Removed.serial(), None);
+        assert_eq!(KeyEvent::Overrun.serial(), None);
+    }
+
+    #[test]
+    fn test_parse_key_events() {
+        let mut buffer = record(WATCH_TYPE_KEY_NOTIFY, NOTIFY_KEY_UPDATED, &[10, 0]);
+        buffer.extend(record(WATCH_TYPE_KEY_NOTIFY, NOTIFY_KEY_LINKED, &[20, 30]));
+        buffer.extend(record(WATCH_TYPE_KEY_NOTIFY, NOTIFY_KEY_SETATTR, &[10, 0]));
+
+        let (events, consumed) = parse_events(&buffer).unwrap();
+        assert_eq!(consumed, buffer.len());
+        assert_eq!(
+            events,
+            [
+                KeyEvent::Updated(serial(10)),
+                KeyEvent::Linked {
+                    keyring: serial(20),
+                    key: serial(30),
+                },
+                KeyEvent::AttributesChanged(serial(10)),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_meta_events() {
+        let mut buffer = record(WATCH_TYPE_META, WATCH_META_LOSS_NOTIFICATION, &[]);
+        buffer.extend(record(
+            WATCH_TYPE_META,
+            WATCH_META_REMOVAL_NOTIFICATION,
+            &[1, 0],
+        ));
+
+        let (events, consumed) = parse_events(&buffer).unwrap();
+        assert_eq!(consumed, buffer.len());
+        assert_eq!(events, [KeyEvent::Overrun, KeyEvent::Removed]);
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let mut buffer = record(WATCH_TYPE_KEY_NOTIFY, NOTIFY_KEY_REVOKED, &[10, 0]);
+        let full = buffer.len();
+        buffer.extend(&record(WATCH_TYPE_KEY_NOTIFY, NOTIFY_KEY_CLEARED, &[10, 0])[..10]);
+
+        let (events, consumed) = parse_events(&buffer).unwrap();
+        assert_eq!(consumed, full);
+        assert_eq!(events, [KeyEvent::Revoked(serial(10))]);
+    }
+
+    #[test]
+    fn test_parse_unknown() {
+        let mut buffer = record(5, 0, &[10, 0]);
+        buffer.extend(record(WATCH_TYPE_KEY_NOTIFY, 100, &[10, 0]));
+
+        let (events, consumed) = parse_events(&buffer).unwrap();
+        assert_eq!(consumed, buffer.len());
+        assert_eq!(events, []);
+    }
+
+    #[test]
+    fn test_parse_bad_length() {
+        let mut buffer = record(WATCH_TYPE_KEY_NOTIFY, NOTIFY_KEY_UPDATED, &[10, 0]);
+        buffer[4..8].copy_from_slice(&0u32.to_ne_bytes());
+
+        let err = parse_events(&buffer).unwrap_err();
+        assert_eq!(err, errno::Errno(libc::EBADMSG));
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use keyutils::{Keyring, SpecialKeyring};
+
+fn session() -> Keyring {
+    Keyring::attach_or_create(SpecialKeyring::Session).unwrap()
+}
+
+#[test]
+fn scoped_anonymous_session() {
+    let name = "scoped_anonymous_session";
+
+    let guard = Keyring::join_anonymous_session_scoped(name).unwrap();
+    assert_eq!(guard.restore(), name);
+    assert_eq!(&session(), guard.keyring());
+    assert_eq!(guard.keyring().description().unwrap().description, "_ses");
+
+    let anonymous = guard.keyring().clone();
+    drop(guard);
+
+    let restored = session();
+    assert_ne!(restored, anonymous);
+    assert_eq!(restored.description().unwrap().description, name);
+
+    restored.invalidate().unwrap()
+}
+
+#[test]
+fn with_anonymous_session() {
+    let name = "with_anonymous_session";
+
+    let anonymous = Keyring::with_anonymous_session(name, |keyring| {
+        assert_eq!(&session(), keyring);
+        keyring.clone()
+    })
+    .unwrap();
+
+    let restored = session();
+    assert_ne!(restored, anonymous);
+    assert_eq!(restored.description().unwrap().description, name);
+
+    restored.invalidate().unwrap()
+}